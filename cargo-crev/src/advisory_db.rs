@@ -0,0 +1,589 @@
+// Importing advisories from the RustSec advisory database as crev proofs
+use crate::{opts, prelude::*, shared::*};
+use crev_data::{
+    proof::{
+        self,
+        review::package::{Advisory, Issue, VersionRange},
+    },
+    Id, Level,
+};
+use crev_lib::{id::Signer, local::Local};
+use std::{
+    collections::BTreeMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+const ADVISORY_DB_URL: &str = "https://github.com/RustSec/advisory-db";
+
+/// Cache directory of the advisory database
+const ADVISORY_DB_CACHE_DIR: &str = "advisory-db";
+
+/// An advisory of the RustSec database, as much of it as crev proofs can hold
+#[derive(Debug, Clone)]
+struct RustsecAdvisory {
+    id: String,
+    package: String,
+    title: String,
+    url: String,
+    aliases: Vec<String>,
+    /// Version requirements of the versions with the fix
+    patched: Vec<String>,
+    severity: Level,
+}
+
+/// An advisory or an issue, to record in the review of a given version
+#[derive(Debug, Clone)]
+enum Finding {
+    Advisory(Advisory),
+    Issue(Issue),
+}
+
+fn toml_str(value: &toml::Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(toml::Value::as_str)
+        .map(str::to_owned)
+}
+
+fn toml_str_list(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(toml::Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(toml::Value::as_str)
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `informational` advisories (eg. unmaintained crates) are of low severity,
+/// and ones with a high impact in their CVSS vector of high severity
+fn severity(advisory: &toml::Value) -> Level {
+    if advisory.get("informational").is_some() {
+        return Level::Low;
+    }
+    match toml_str(advisory, "cvss") {
+        Some(ref cvss) if ["/C:H", "/I:H", "/A:H"].iter().any(|m| cvss.contains(m)) => Level::High,
+        _ => Level::Medium,
+    }
+}
+
+/// Parse an advisory file, either TOML or Markdown with a TOML front matter
+///
+/// Returns `None` for advisories not about a crate (eg. about the Rust toolchain),
+/// and withdrawn ones.
+fn parse_advisory(path: &Path) -> Result<Option<RustsecAdvisory>> {
+    let content = crev_common::read_file_to_string(path)?;
+    parse_advisory_str(content, path.extension() == Some(OsStr::new("md")))
+}
+
+fn parse_advisory_str(content: String, is_markdown: bool) -> Result<Option<RustsecAdvisory>> {
+    let (front, mut title) = if is_markdown {
+        let start = content
+            .find("```toml")
+            .ok_or_else(|| format_err!("No TOML front matter"))?;
+        let front = &content[start + "```toml".len()..];
+        let end = front
+            .find("```")
+            .ok_or_else(|| format_err!("Unterminated TOML front matter"))?;
+        let title = front[end..]
+            .lines()
+            .find(|line| line.starts_with("# "))
+            .map(|line| line[2..].trim().to_owned());
+        (front[..end].to_owned(), title)
+    } else {
+        (content, None)
+    };
+
+    let value: toml::Value = toml::from_str(&front)?;
+    let advisory = value
+        .get("advisory")
+        .ok_or_else(|| format_err!("No `[advisory]` section"))?;
+    if toml_str(advisory, "collection").as_deref() == Some("rust")
+        || advisory.get("withdrawn").is_some()
+    {
+        return Ok(None);
+    }
+    let package = match toml_str(advisory, "package") {
+        Some(package) => package,
+        None => return Ok(None),
+    };
+    let id = toml_str(advisory, "id").ok_or_else(|| format_err!("No advisory id"))?;
+    if title.is_none() {
+        title = toml_str(advisory, "title");
+    }
+    // the newer format moved the versions into a section of their own
+    let patched = match value.get("versions") {
+        Some(versions) => toml_str_list(versions.get("patched")),
+        None => toml_str_list(advisory.get("patched_versions")),
+    };
+
+    Ok(Some(RustsecAdvisory {
+        url: toml_str(advisory, "url")
+            .unwrap_or_else(|| format!("https://rustsec.org/advisories/{}.html", id)),
+        aliases: toml_str_list(advisory.get("aliases")),
+        title: title.unwrap_or_default(),
+        severity: severity(advisory),
+        id,
+        package,
+        patched,
+    }))
+}
+
+/// Parse `1.2.3`, but also `1.2` or `1`, as used in version requirements
+fn parse_partial_version(s: &str) -> Option<Version> {
+    let s = s.trim();
+    let dots = s.split('-').next().unwrap_or("").matches('.').count();
+    let padded = match dots {
+        0 => format!("{}.0.0", s),
+        1 => format!("{}.0", s),
+        _ => s.to_owned(),
+    };
+    Version::parse(&padded).ok()
+}
+
+/// Version a patched version requirement starts at, and the range of older versions it fixes
+///
+/// crev ranges are coarser than version requirements, so this is an approximation.
+fn patched_version_range(req: &str, is_lowest: bool) -> Option<(Version, VersionRange)> {
+    let mut parts = req.split(',').map(str::trim);
+    let first = parts.next()?;
+    let upper = parts
+        .find(|part| part.starts_with('<'))
+        .and_then(|part| parse_partial_version(part.trim_start_matches(&['<', '='][..])));
+
+    let op_len = first
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(first.len());
+    let (op, version) = first.split_at(op_len);
+    let mut version = parse_partial_version(version)?;
+    let op = op.trim();
+
+    let range = match op {
+        "" | "^" if version.major == 0 => VersionRange::Minor,
+        "" | "^" => VersionRange::Major,
+        "~" | "=" => VersionRange::Minor,
+        ">=" | ">" => {
+            if op == ">" {
+                version.increment_patch();
+            }
+            match upper {
+                Some(ref upper) if upper.major == version.major => VersionRange::Minor,
+                Some(_) => VersionRange::Major,
+                None if is_lowest => VersionRange::All,
+                None => VersionRange::Major,
+            }
+        }
+        _ => return None,
+    };
+    Some((version, range))
+}
+
+/// Findings of an advisory, by the version to record them in
+///
+/// A patched version gets an advisory for the older ones. Without any, the issue
+/// is reported for all the versions.
+fn findings(advisory: &RustsecAdvisory) -> Vec<(Version, Finding)> {
+    let mut ids = vec![advisory.id.clone()];
+    ids.extend(advisory.aliases.iter().cloned());
+    let comment = format!("{}\n\n{}", advisory.title, advisory.url)
+        .trim()
+        .to_owned();
+
+    let lowest = advisory
+        .patched
+        .iter()
+        .filter_map(|req| patched_version_range(req, false))
+        .map(|(version, _)| version)
+        .min();
+    let mut findings: Vec<_> = advisory
+        .patched
+        .iter()
+        .filter_map(|req| {
+            let (version, _) = patched_version_range(req, false)?;
+            patched_version_range(req, Some(&version) == lowest.as_ref())
+        })
+        .map(|(version, range)| {
+            (
+                version,
+                Finding::Advisory(Advisory {
+                    ids: ids.clone(),
+                    severity: advisory.severity,
+                    range,
+                    comment: comment.clone(),
+                }),
+            )
+        })
+        .collect();
+
+    if findings.is_empty() {
+        findings.push((
+            Version::new(0, 0, 0),
+            Finding::Issue(Issue {
+                id: advisory.id.clone(),
+                severity: advisory.severity,
+                range: VersionRange::All,
+                comment,
+            }),
+        ));
+    }
+    findings
+}
+
+fn advisory_files(db_path: &Path) -> Vec<PathBuf> {
+    let crates_dir = db_path.join("crates");
+    let dir = if crates_dir.exists() {
+        crates_dir
+    } else {
+        db_path.to_owned()
+    };
+    let mut files: Vec<_> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .and_then(OsStr::to_str)
+                    .unwrap_or("")
+                    .starts_with("RUSTSEC-")
+                && (path.extension() == Some(OsStr::new("toml"))
+                    || path.extension() == Some(OsStr::new("md")))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Reviews created by this importer, as opposed to ones written by hand
+fn is_imported(review: &proof::review::Package) -> bool {
+    review.package.digest.iter().all(|&b| b == 0) && review.review.is_none()
+}
+
+fn already_recorded(review: &proof::review::Package, finding: &Finding) -> bool {
+    match finding {
+        Finding::Advisory(advisory) => review
+            .advisories
+            .iter()
+            .any(|a| a.ids.contains(&advisory.ids[0]) && a.range == advisory.range),
+        Finding::Issue(issue) => review.issues.iter().any(|i| i.id == issue.id),
+    }
+}
+
+/// Sign the advisories of the RustSec database as package reviews of the given
+/// (or current) Id
+///
+/// The reviews don't review any code, so they have an all-zero digest, which never
+/// matches nor verifies a crate. Only reviews created this way are updated: if the
+/// Id has reviewed one of the versions itself, nothing is imported.
+pub fn import_advisory_db(args: &opts::RepoImportAdvisoryDb) -> Result<()> {
+    let local = Local::auto_open()?;
+    let db_path = match args.path {
+        Some(ref path) => path.clone(),
+        None => {
+            eprintln!("Fetching {}...", ADVISORY_DB_URL);
+            local.fetch_git_into_cache(ADVISORY_DB_URL, ADVISORY_DB_CACHE_DIR)?
+        }
+    };
+
+    let mut by_version: BTreeMap<(String, Version), Vec<Finding>> = BTreeMap::new();
+    let mut nb_advisories = 0;
+    for path in advisory_files(&db_path) {
+        let advisory = match parse_advisory(&path) {
+            Ok(Some(advisory)) => advisory,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        if !args.crate_.is_empty() && !args.crate_.contains(&advisory.package) {
+            continue;
+        }
+        nb_advisories += 1;
+        for (version, finding) in findings(&advisory) {
+            by_version
+                .entry((advisory.package.clone(), version))
+                .or_default()
+                .push(finding);
+        }
+    }
+
+    let signer = match args.id {
+        Some(ref id) => Signer::Local(
+            local.read_unlocked_id(&Id::crevid_from_str(id)?, &crev_common::read_passphrase)?,
+        ),
+        None => local.read_current_signer(&crev_common::read_passphrase)?,
+    };
+    let from = signer.as_pubid().to_owned();
+    let db = local.load_db()?;
+    // commit once, after all the proofs are stored
+    let store_opts = opts::CommonProofCreate {
+        no_commit: true,
+        ..args.common.clone()
+    };
+
+    let own_reviews: Vec<_> = by_version
+        .keys()
+        .filter_map(|(name, version)| {
+            db.get_pkg_review(PROJECT_SOURCE_CRATES_IO, name, version, &from.id)
+        })
+        .filter(|review| !is_imported(review))
+        .collect();
+    if !own_reviews.is_empty() {
+        for review in &own_reviews {
+            eprintln!(
+                "{} {} has a review of your own by {}",
+                review.package.name, review.package.version, from.id
+            );
+        }
+        bail!("Not adding advisories to reviews written by hand; pass a separate Id to sign them with `--id`");
+    }
+
+    let mut nb_proofs = 0;
+    for ((name, version), findings) in by_version {
+        let mut review =
+            match db.get_pkg_review(PROJECT_SOURCE_CRATES_IO, &name, &version, &from.id) {
+                Some(review) => review.clone(),
+                None => proof::review::PackageBuilder::default()
+                    .from(from.clone())
+                    .package(proof::PackageInfo {
+                        id: None,
+                        source: PROJECT_SOURCE_CRATES_IO.to_owned(),
+                        name: name.clone(),
+                        version: version.clone(),
+                        digest: vec![0; 32],
+                        digest_type: proof::default_digest_type(),
+                        revision: String::new(),
+                        revision_type: proof::default_revision_type(),
+                    })
+                    .review(crev_data::Review::new_none())
+                    .comment("Imported from the RustSec advisory database".into())
+                    .build()
+                    .map_err(|e| format_err!("{}", e))?,
+            };
+        let findings: Vec<_> = findings
+            .into_iter()
+            .filter(|finding| !already_recorded(&review, finding))
+            .collect();
+        if findings.is_empty() {
+            continue;
+        }
+        for finding in findings {
+            match finding {
+                Finding::Advisory(advisory) => review.advisories.push(advisory),
+                Finding::Issue(issue) => review.issues.push(issue),
+            }
+        }
+        review.date = crev_common::now();
+
+        let proof = signer.sign(&review.into())?;
+        maybe_store(&local, &proof, "", &store_opts)?;
+        nb_proofs += 1;
+    }
+
+    if nb_proofs > 0 && !args.common.no_store && !args.common.no_commit {
+        local.proof_dir_commit("Import RustSec advisories")?;
+    }
+    eprintln!(
+        "{} advisor{} found, {} proof{} created",
+        nb_advisories,
+        if nb_advisories == 1 { "y" } else { "ies" },
+        nb_proofs,
+        if nb_proofs == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str, is_markdown: bool) -> Option<RustsecAdvisory> {
+        parse_advisory_str(content.to_owned(), is_markdown).unwrap()
+    }
+
+    fn range(req: &str, is_lowest: bool) -> Option<(String, VersionRange)> {
+        patched_version_range(req, is_lowest).map(|(version, range)| (version.to_string(), range))
+    }
+
+    #[test]
+    fn parse_toml_advisory() {
+        let advisory = parse(
+            r#"
+[advisory]
+id = "RUSTSEC-2019-0001"
+package = "foo"
+title = "Uncontrolled recursion leads to abort"
+patched_versions = [">= 1.2.3", "^0.9.5"]
+"#,
+            false,
+        )
+        .unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2019-0001");
+        assert_eq!(advisory.package, "foo");
+        assert_eq!(advisory.title, "Uncontrolled recursion leads to abort");
+        assert_eq!(
+            advisory.url,
+            "https://rustsec.org/advisories/RUSTSEC-2019-0001.html"
+        );
+        assert_eq!(advisory.patched, vec![">= 1.2.3", "^0.9.5"]);
+        assert_eq!(advisory.severity, Level::Medium);
+    }
+
+    #[test]
+    fn parse_markdown_advisory() {
+        let advisory = parse(
+            r#"```toml
+[advisory]
+id = "RUSTSEC-2020-0002"
+package = "foo"
+aliases = ["CVE-2020-1234"]
+cvss = "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N"
+
+[versions]
+patched = [">= 1.2.3"]
+```
+
+# Memory exposure
+
+Details.
+"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2020-0002");
+        assert_eq!(advisory.title, "Memory exposure");
+        assert_eq!(advisory.aliases, vec!["CVE-2020-1234"]);
+        assert_eq!(advisory.patched, vec![">= 1.2.3"]);
+        assert_eq!(advisory.severity, Level::High);
+
+        let unmaintained = parse(
+            r#"```toml
+[advisory]
+id = "RUSTSEC-2021-0003"
+package = "bar"
+informational = "unmaintained"
+
+[versions]
+patched = []
+```
+"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(unmaintained.severity, Level::Low);
+        assert!(unmaintained.patched.is_empty());
+
+        assert!(parse_advisory_str("# No front matter".into(), true).is_err());
+    }
+
+    #[test]
+    fn skip_advisories_not_about_live_crates() {
+        assert!(parse(
+            r#"
+[advisory]
+id = "RUSTSEC-2021-0004"
+package = "std"
+collection = "rust"
+"#,
+            false,
+        )
+        .is_none());
+        assert!(parse(
+            r#"
+[advisory]
+id = "RUSTSEC-2021-0005"
+package = "foo"
+withdrawn = "2021-02-01"
+"#,
+            false,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn parse_partial_versions() {
+        assert_eq!(parse_partial_version("1"), Some(Version::new(1, 0, 0)));
+        assert_eq!(parse_partial_version(" 1.2 "), Some(Version::new(1, 2, 0)));
+        assert_eq!(parse_partial_version("1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(
+            parse_partial_version("1.2.3-alpha.1"),
+            Version::parse("1.2.3-alpha.1").ok()
+        );
+        assert_eq!(parse_partial_version("x"), None);
+    }
+
+    #[test]
+    fn map_patched_version_requirements() {
+        use VersionRange::{All, Major, Minor};
+        let check = |req, is_lowest, version: &str, expected| {
+            assert_eq!(range(req, is_lowest), Some((version.to_owned(), expected)));
+        };
+
+        check("1.2.3", false, "1.2.3", Major);
+        check("0.9.5", false, "0.9.5", Minor);
+        check("^1.2.3", true, "1.2.3", Major);
+        check("^0.9.5", true, "0.9.5", Minor);
+        check("~1.2.3", false, "1.2.3", Minor);
+        check("= 1.2.3", false, "1.2.3", Minor);
+        check(">= 1.2.3", true, "1.2.3", All);
+        check(">= 1.2.3", false, "1.2.3", Major);
+        check("> 1.2.3", true, "1.2.4", All);
+        check(">= 1.2.3, < 1.3", true, "1.2.3", Minor);
+        check(">= 1.2.3, < 2.0.0", true, "1.2.3", Major);
+        assert_eq!(range("< 1.2.3", true), None);
+        assert_eq!(range("latest", true), None);
+    }
+
+    #[test]
+    fn findings_by_patched_version() {
+        let mut advisory = RustsecAdvisory {
+            id: "RUSTSEC-2019-0001".into(),
+            package: "foo".into(),
+            title: "Title".into(),
+            url: "https://rustsec.org/advisories/RUSTSEC-2019-0001.html".into(),
+            aliases: vec!["CVE-2019-1234".into()],
+            patched: vec![">= 1.2.3".into(), "^0.9.5".into()],
+            severity: Level::High,
+        };
+
+        let findings_of = |advisory: &RustsecAdvisory| -> Vec<_> {
+            findings(advisory)
+                .into_iter()
+                .map(|(version, finding)| match finding {
+                    Finding::Advisory(a) => {
+                        assert_eq!(a.ids, vec!["RUSTSEC-2019-0001", "CVE-2019-1234"]);
+                        assert_eq!(a.severity, Level::High);
+                        assert_eq!(
+                            a.comment,
+                            "Title\n\nhttps://rustsec.org/advisories/RUSTSEC-2019-0001.html"
+                        );
+                        (version.to_string(), Some(a.range))
+                    }
+                    Finding::Issue(issue) => {
+                        assert_eq!(issue.id, "RUSTSEC-2019-0001");
+                        assert_eq!(issue.range, VersionRange::All);
+                        (version.to_string(), None)
+                    }
+                })
+                .collect()
+        };
+
+        // only the lowest patched version covers all the older ones
+        assert_eq!(
+            findings_of(&advisory),
+            vec![
+                ("1.2.3".to_owned(), Some(VersionRange::Major)),
+                ("0.9.5".to_owned(), Some(VersionRange::Minor)),
+            ]
+        );
+
+        advisory.patched = vec![];
+        assert_eq!(findings_of(&advisory), vec![("0.0.0".to_owned(), None)]);
+    }
+}
@@ -1,15 +1,60 @@
 use crate::prelude::*;
+use failure::format_err;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
+    collections::BTreeMap,
     fs,
     io::Read,
     path::{Path, PathBuf},
+    sync::Mutex,
     time::Duration,
 };
 
+/// Retries of failed API requests, unless configured otherwise
+pub const DEFAULT_RETRIES: u32 = 2;
+
+/// Delay before the first retry, doubled after each one
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+const DEFAULT_API_URL: &str = "https://crates.io/api/v1/";
+
+const USER_AGENT: &str = concat!(
+    "cargo-crev/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/crev-dev/cargo-crev)"
+);
+
+/// HTTP client honoring `HTTP_PROXY` and `HTTPS_PROXY`, with the cargo-crev user agent
+pub fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .use_sys_proxy()
+        .default_headers(
+            std::iter::once((
+                reqwest::header::USER_AGENT,
+                reqwest::header::HeaderValue::from_static(USER_AGENT),
+            ))
+            .collect(),
+        )
+        .build()?)
+}
+
 pub struct Client {
-    client: crates_io_api::SyncClient,
+    client: reqwest::Client,
+    api_url: reqwest::Url,
     cache_dir: PathBuf,
+    retries: u32,
+    // reviewed owners files are read, modified and written back from many scanner threads
+    reviewed_owners_lock: Mutex<()>,
+}
+
+/// Parse the base URL of the API, making sure relative paths get appended to it
+fn parse_api_url(url: &str) -> Result<reqwest::Url> {
+    let url = if url.ends_with('/') {
+        url.to_owned()
+    } else {
+        format!("{}/", url)
+    };
+    reqwest::Url::parse(&url).map_err(|e| format_err!("Invalid crates.io API URL: {}", e))
 }
 
 fn is_fresh(path: &Path) -> Result<bool> {
@@ -21,15 +66,17 @@ fn is_fresh(path: &Path) -> Result<bool> {
 
 trait Cacheable: Sized {
     fn get_cache_path(base: &Path, name: &str, version: &str) -> PathBuf;
-    fn fetch(client: &crates_io_api::SyncClient, crate_: &str, _version: &str) -> Result<Self>;
+    fn fetch(client: &Client, crate_: &str, _version: &str) -> ApiResult<Self>;
 }
 
+type ApiResult<T> = std::result::Result<T, crates_io_api::Error>;
+
 impl Cacheable for crates_io_api::CrateResponse {
     fn get_cache_path(base: &Path, name: &str, _version: &str) -> PathBuf {
         base.join("crate").join(format!("{}.json", name))
     }
-    fn fetch(client: &crates_io_api::SyncClient, crate_: &str, _version: &str) -> Result<Self> {
-        Ok(client.get_crate(crate_)?)
+    fn fetch(client: &Client, crate_: &str, _version: &str) -> ApiResult<Self> {
+        client.get_json(&format!("crates/{}", crate_))
     }
 }
 
@@ -37,10 +84,8 @@ impl Cacheable for crates_io_api::Owners {
     fn get_cache_path(base: &Path, name: &str, _version: &str) -> PathBuf {
         base.join("owners").join(format!("{}.json", name))
     }
-    fn fetch(client: &crates_io_api::SyncClient, crate_: &str, _version: &str) -> Result<Self> {
-        Ok(crates_io_api::Owners {
-            users: client.crate_owners(crate_)?,
-        })
+    fn fetch(client: &Client, crate_: &str, _version: &str) -> ApiResult<Self> {
+        client.get_json(&format!("crates/{}/owners", crate_))
     }
 }
 
@@ -59,12 +104,41 @@ impl Client {
     pub fn new(local: &crev_lib::Local) -> Result<Self> {
         let cache_dir = local.get_root_cache_dir().join("crates_io");
         fs::create_dir_all(&cache_dir)?;
+        let api_url = local
+            .load_user_config()?
+            .crates_io_api
+            .unwrap_or_else(|| DEFAULT_API_URL.to_owned());
         Ok(Self {
-            client: crates_io_api::SyncClient::new(),
+            client: http_client()?,
+            api_url: parse_api_url(&api_url)?,
             cache_dir,
+            retries: DEFAULT_RETRIES,
+            reviewed_owners_lock: Mutex::new(()),
+        })
+    }
+
+    /// Use the crates.io API (or a mirror of it) at `url`
+    pub fn with_api_url(self, url: &str) -> Result<Self> {
+        Ok(Self {
+            api_url: parse_api_url(url)?,
+            ..self
         })
     }
 
+    fn get_json<T: DeserializeOwned>(&self, path: &str) -> ApiResult<T> {
+        let url = self.api_url.join(path)?;
+        let res = self.client.get(url).send()?;
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(crates_io_api::Error::NotFound);
+        }
+        Ok(res.error_for_status()?.json()?)
+    }
+
+    /// Retry failed API requests up to `retries` times, with exponential backoff
+    pub fn with_retries(self, retries: u32) -> Self {
+        Self { retries, ..self }
+    }
+
     fn load_cache(&self, path: &Path) -> Result<String> {
         let mut file = std::fs::File::open(path)?;
         let mut content = String::new();
@@ -94,11 +168,27 @@ impl Client {
     }
 
     fn fetch<T: Cacheable + Serialize>(&self, crate_: &str, version: &str) -> Result<T> {
-        let resp = T::fetch(&self.client, crate_, version)?;
+        let resp = self.fetch_with_retries::<T>(crate_, version)?;
         self.store_in_cache(&T::get_cache_path(&self.cache_dir, crate_, version), &resp)?;
         Ok(resp)
     }
 
+    fn fetch_with_retries<T: Cacheable>(&self, crate_: &str, version: &str) -> Result<T> {
+        let mut retries_left = self.retries;
+        let mut delay = INITIAL_RETRY_DELAY;
+        loop {
+            match T::fetch(self, crate_, version) {
+                // only network errors are worth retrying
+                Err(crates_io_api::Error::Http(_)) if retries_left > 0 => {
+                    std::thread::sleep(delay);
+                    retries_left -= 1;
+                    delay *= 2;
+                }
+                res => return Ok(res?),
+            }
+        }
+    }
+
     fn get<T: Cacheable + DeserializeOwned + Serialize>(
         &self,
         crate_: &str,
@@ -127,4 +217,87 @@ impl Client {
         let owners = self.get::<crates_io_api::Owners>(crate_, "")?;
         Ok(owners.users.into_iter().map(|u| u.login).collect())
     }
+
+    fn get_reviewed_owners_path(&self, crate_: &str) -> PathBuf {
+        self.cache_dir
+            .join("reviewed_owners")
+            .join(format!("{}.json", crate_))
+    }
+
+    fn load_reviewed_owners(&self, crate_: &str) -> Result<BTreeMap<String, Vec<String>>> {
+        let path = self.get_reviewed_owners_path(crate_);
+        if path.exists() {
+            Ok(serde_json::from_str(&self.load_cache(&path)?)?)
+        } else {
+            Ok(BTreeMap::new())
+        }
+    }
+
+    /// Owners of a crate, as seen when its `version` was first found reviewed
+    pub fn get_reviewed_owners(
+        &self,
+        crate_: &str,
+        version: &Version,
+    ) -> Result<Option<Vec<String>>> {
+        Ok(self
+            .load_reviewed_owners(crate_)?
+            .remove(&version.to_string()))
+    }
+
+    /// Remember owners of a reviewed crate version, unless already known
+    pub fn record_reviewed_owners(
+        &self,
+        crate_: &str,
+        version: &Version,
+        owners: &[String],
+    ) -> Result<()> {
+        let _lock = self.reviewed_owners_lock.lock().unwrap();
+        let mut reviewed_owners = self.load_reviewed_owners(crate_)?;
+        if reviewed_owners.contains_key(&version.to_string()) {
+            return Ok(());
+        }
+        reviewed_owners.insert(version.to_string(), owners.to_vec());
+        let path = self.get_reviewed_owners_path(crate_);
+        fs::create_dir_all(path.parent().expect("not a root path"))?;
+        crev_common::store_to_file_with(&path, |file| {
+            serde_json::to_writer(file, &reviewed_owners)
+        })??;
+        Ok(())
+    }
+
+    /// Replace the `from` owner with `to` in the recorded owners of all
+    /// reviewed crates, returning how many crates changed
+    pub fn rename_reviewed_owner(&self, from: &str, to: &str) -> Result<usize> {
+        let dir = self.cache_dir.join("reviewed_owners");
+        if !dir.exists() {
+            return Ok(0);
+        }
+        let _lock = self.reviewed_owners_lock.lock().unwrap();
+        let mut count = 0;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let mut reviewed_owners: BTreeMap<String, Vec<String>> =
+                serde_json::from_str(&self.load_cache(&path)?)?;
+            let mut changed = false;
+            for owner in reviewed_owners
+                .values_mut()
+                .flat_map(|owners| owners.iter_mut())
+            {
+                if owner == from {
+                    *owner = to.to_owned();
+                    changed = true;
+                }
+            }
+            if changed {
+                crev_common::store_to_file_with(&path, |file| {
+                    serde_json::to_writer(file, &reviewed_owners)
+                })??;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
 }
@@ -6,13 +6,24 @@ use crev_lib::*;
 
 use crate::{opts::*, prelude::*, shared::*, term};
 use cargo::core::PackageId;
+use crev_common::convert::OptionDeref;
 use std::{
     collections::{HashMap, HashSet},
     ops::Add,
+    time::Duration,
 };
 
+mod budget;
+mod counts;
+mod json_lines;
+mod markdown;
 mod print_term;
+mod report;
+mod review_missing;
+mod sarif;
 pub mod scan;
+mod sort;
+pub mod watch;
 
 #[derive(Copy, Clone, Debug)]
 /// Progress-bar kind of thing, you know?
@@ -148,16 +159,40 @@ impl std::ops::Add<AccumulativeCrateDetails> for AccumulativeCrateDetails {
     }
 }
 
+/// Advisory for a crate version, reported by a trusted reviewer
+#[derive(Clone, Debug)]
+pub struct TrustedAdvisory {
+    pub author: PubId,
+    pub severity: crev_data::Level,
+    pub ids: Vec<String>,
+    pub comment: String,
+}
+
 /// Crate statistics - details
 #[derive(Clone, Debug)]
 pub struct CrateDetails {
     pub digest: Digest,
+    /// Digest type `digest` was calculated with, matching the reviews if possible
+    pub digest_type: String,
     pub latest_trusted_version: Option<Version>,
     pub trusted_reviewers: HashSet<PubId>,
+    /// Advisories affecting this version, from trusted reviewers
+    pub trusted_advisories: Vec<TrustedAdvisory>,
+    /// Ids of open issues affecting this version, reported by trusted reviewers
+    pub trusted_issue_ids: Vec<String>,
+    /// Number of trusted reviewers of this version, with distinct proof repositories
+    pub independent_reviewers: u64,
+    /// Date of the most recent review of this version by a trusted reviewer
+    pub latest_trusted_review_date: Option<crev_data::proof::Date>,
     pub version_reviews: CountWithTotal,
     pub version_downloads: Option<CountWithTotal>,
     pub known_owners: Option<CountWithTotal>,
+    /// Owner logins, as reported by crates.io
+    pub owner_list: Option<Vec<String>>,
+    /// Owners differ from those seen when the latest trusted version was reviewed
+    pub owners_changed: Option<bool>,
     pub unclean_digest: bool,
+    pub redownloaded: bool,
     pub accumulative_own: AccumulativeCrateDetails,
     pub accumulative: AccumulativeCrateDetails,
 }
@@ -212,12 +247,32 @@ impl PartialEq for CrateInfo {
 
 impl Eq for CrateInfo {}
 
+/// Wall-clock time spent in each phase of scanning a crate
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CrateTimings {
+    pub download: Duration,
+    pub digest: Duration,
+    pub geiger: Duration,
+    pub loc: Duration,
+}
+
+impl CrateTimings {
+    pub fn total(&self) -> Duration {
+        self.download + self.digest + self.geiger + self.loc
+    }
+}
+
 /// A dependency, as returned by the computer. It may
 ///  contain (depending on success/slipping) the computed
 ///  dep.
 pub struct CrateStats {
     pub info: CrateInfo,
     pub details: Result<Option<CrateDetails>>,
+    pub timings: CrateTimings,
+    /// The same version was already locked at `--baseline`
+    pub in_baseline: bool,
+    /// Matched by `--ignore` or `.crev/ignore`, so it doesn't need reviews
+    pub ignored: bool,
 }
 
 impl CrateStats {
@@ -247,6 +302,63 @@ impl CrateStats {
     }
 }
 
+/// Whether `s` matches `pattern`, where `*` matches any characters and `?` a single one
+fn wildcard_matches(pattern: &[u8], s: &[u8]) -> bool {
+    match (pattern.first(), s.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_matches(&pattern[1..], s)
+                || (!s.is_empty() && wildcard_matches(pattern, &s[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_matches(&pattern[1..], &s[1..]),
+        (Some(p), Some(c)) if p == c => wildcard_matches(&pattern[1..], &s[1..]),
+        _ => false,
+    }
+}
+
+/// A crate that doesn't require reviews, as `name` or `name = <version requirement>`
+///
+/// The name can contain `*` and `?` wildcards. Also used to exclude crates from `crate verify`.
+#[derive(Clone, Debug)]
+pub struct IgnorePattern {
+    pub name: String,
+    pub version_req: Option<semver::VersionReq>,
+}
+
+impl IgnorePattern {
+    pub fn matches(&self, name: &str, version: &Version) -> bool {
+        wildcard_matches(self.name.as_bytes(), name.as_bytes())
+            && match self.version_req {
+                Some(ref version_req) => version_req.matches(version),
+                None => true,
+            }
+    }
+}
+
+impl std::str::FromStr for IgnorePattern {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.splitn(2, &['=', '@'][..]);
+        let name = parts.next().unwrap_or("").trim();
+        if name.is_empty() {
+            bail!("Crate name missing in ignore pattern: {}", s);
+        }
+        let version_req = match parts.next().map(|req| req.trim().trim_matches('"')) {
+            Some(req) => Some(
+                semver::VersionReq::parse(req)
+                    .map_err(|e| format_err!("Invalid version range in `{}`: {}", s, e))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            name: name.to_owned(),
+            version_req,
+        })
+    }
+}
+
 pub fn latest_trusted_version_string(
     base_version: &Version,
     latest_trusted_version: &Option<Version>,
@@ -272,7 +384,18 @@ pub fn latest_trusted_version_string(
     }
 }
 
-pub fn crate_mvps(common: CrateVerifyCommon) -> Result<()> {
+pub fn crate_mvps(args: CrateMvp) -> Result<()> {
+    let CrateMvp {
+        common,
+        min_reviews,
+        top,
+    } = args;
+
+    // without an id the trust set is empty, and there would be no MVPs to show;
+    // `--for-id` allows looking at someone else's WoT
+    let local = crev_lib::Local::auto_create_or_open()?;
+    local.get_for_id_from_str(OptionDeref::as_deref(&common.for_id))?;
+
     let mut args = CrateVerify::default();
     args.common = common;
 
@@ -290,6 +413,10 @@ pub fn crate_mvps(common: CrateVerifyCommon) -> Result<()> {
     let mut mvps: Vec<_> = mvps.into_iter().collect();
 
     mvps.sort_by(|a, b| a.1.cmp(&b.1).reverse());
+    mvps.retain(|(_id, count)| *count >= min_reviews);
+    if let Some(top) = top {
+        mvps.truncate(top);
+    }
 
     for (id, count) in &mvps {
         println!("{:>3} {} {}", count, id.id, id.url.url);
@@ -298,46 +425,359 @@ pub fn crate_mvps(common: CrateVerifyCommon) -> Result<()> {
     Ok(())
 }
 
-pub fn verify_deps(args: CrateVerify) -> Result<CommandExitStatus> {
+/// Trusted reviewers reported issues in the crate itself, listed by `print_term::print_issues`
+fn has_trusted_issues(stats: &CrateStats) -> bool {
+    stats
+        .details()
+        .map(|details| !details.trusted_issue_ids.is_empty())
+        .unwrap_or(false)
+}
+
+pub fn verify_deps(mut args: CrateVerify) -> Result<CommandExitStatus> {
     let mut term = term::Term::new();
+    if args.width.is_some() {
+        term.width = args.width;
+    }
+
+    if args.recursive_depth.is_some() {
+        args.recursive = true;
+    }
+    match args.rows {
+        VerifyRows::All => {}
+        VerifyRows::Direct => args.skip_indirect = true,
+        VerifyRows::TransitiveOnly => {
+            // roll up everything below each direct dependency into its row
+            args.skip_indirect = true;
+            args.recursive = true;
+        }
+    }
+
+    let budget = match args.budget {
+        Some(ref path) => Some(budget::Budget::read_from(path)?),
+        None => None,
+    };
 
     let scanner = scan::Scanner::new(&args)?;
+    if let Some(ref name) = args.explain {
+        scanner.explain(name)?;
+        return Ok(CommandExitStatus::Success);
+    }
+    let nb_pruned = scanner.pruned_crate_count();
+    let depths = if args.max_depth_path {
+        Some(scanner.dependency_depths())
+    } else {
+        None
+    };
     let events = scanner.run();
 
+    let text_output = args.output_format == VerifyOutputFormat::Text && !args.count_only;
+    let show_review_date = args.show_review_date || args.stale_after.is_some();
+    let known_owners = if args.show_owners {
+        read_known_owners_with(OptionDeref::as_deref(&args.owners_from))?
+    } else {
+        HashSet::new()
+    };
+
     // print header, only after `scanner` had a chance to download everything
-    if term.stderr_is_tty && term.stdout_is_tty {
-        self::print_term::print_header(&mut term, args.verbose);
+    if text_output && term.stderr_is_tty && term.stdout_is_tty {
+        self::print_term::print_header(
+            &mut term,
+            args.verbose,
+            show_review_date,
+            args.require_reviewers.is_some(),
+            args.recursive,
+            !args.no_accumulate,
+        );
     }
 
-    let deps: Vec<_> = events
+    let mut json_lines = match args.json_lines_to {
+        Some(ref path) => Some(json_lines::JsonLinesWriter::open(path)?),
+        None => None,
+    };
+
+    // sorting and grouping need all the rows, so only then they can be printed
+    let print_rows = |deps: &[CrateStats], term: &mut term::Term| -> Result<()> {
+        if !text_output {
+            return Ok(());
+        }
+        for stats in deps {
+            if args.only_with_issues && !has_trusted_issues(stats) {
+                continue;
+            }
+            print_term::print_dep(
+                stats,
+                term,
+                args.verbose,
+                args.recursive && !args.no_accumulate,
+                show_review_date,
+                args.stale_after,
+                args.require_reviewers,
+            )?;
+            if args.show_owners {
+                print_term::print_owners(stats, term, &known_owners)?;
+            }
+            if args.show_review_sources {
+                print_term::print_review_sources(stats);
+            }
+            if args.only_with_issues {
+                print_term::print_issues(stats, term)?;
+            }
+            if args.annotate_advisories {
+                print_term::print_advisories(stats, term)?;
+            }
+        }
+        Ok(())
+    };
+
+    let mut deps: Vec<_> = events
         .into_iter()
         .map(|stats| {
-            print_term::print_dep(&stats, &mut term, args.verbose, args.recursive)?;
+            if let Some(ref mut json_lines) = json_lines {
+                json_lines.write(&stats)?;
+            }
+            if args.sort.is_none() && !args.group_by_trust {
+                print_rows(std::slice::from_ref(&stats), &mut term)?;
+            }
             Ok(stats)
         })
         .collect::<Result<_>>()?;
 
+    if let Some(key) = args.sort {
+        sort::sort_deps(&mut deps, key, args.reverse);
+    }
+    if args.group_by_trust {
+        sort::group_deps_by_trust(&mut deps);
+        let mut rest = &deps[..];
+        while let Some(first) = rest.first() {
+            let group = sort::trust_group(first);
+            let len = rest
+                .iter()
+                .take_while(|stats| sort::trust_group(stats) == group)
+                .count();
+            let (rows, tail) = rest.split_at(len);
+            if let Some(group) = group {
+                if text_output {
+                    println!("{} ({}):", group.title(), rows.len());
+                }
+                print_rows(rows, &mut term)?;
+            }
+            rest = tail;
+        }
+    } else if args.sort.is_some() {
+        print_rows(&deps, &mut term)?;
+    }
+
+    if args.timings {
+        print_term::print_timings(&deps);
+    }
+    if let Some(ref depths) = depths {
+        print_term::print_dependency_depths(depths);
+    }
+
+    if args.count_only {
+        let counts = counts::VerifyCounts::new(&deps);
+        if args.output_format == VerifyOutputFormat::Json {
+            counts.print_json();
+        } else {
+            counts.print_text();
+        }
+    } else if args.output_format == VerifyOutputFormat::Sarif {
+        sarif::print_sarif(&deps)?;
+    } else if args.output_format == VerifyOutputFormat::Markdown {
+        markdown::print_markdown(&deps);
+    } else if args.output_format == VerifyOutputFormat::Json {
+        let crates: Vec<_> = deps.iter().map(json_lines::stats_to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&crates)?);
+    }
+
     let mut nb_unclean_digests = 0;
     let mut nb_unverified = 0;
+    let mut nb_unverified_in_baseline = 0;
+    // unverified (by the scan) for lack of independent reviewers; part of `nb_unverified`
+    let mut nb_missing_reviewers = 0;
     for dep in &deps {
-        if dep.is_digest_unclean() {
+        let missing_reviewers = match (args.require_reviewers, dep.details()) {
+            (Some(require_reviewers), Some(details)) => {
+                details.independent_reviewers < require_reviewers
+            }
+            _ => false,
+        };
+        if dep.is_digest_unclean() || missing_reviewers {
             let details = dep.details().unwrap();
             if details.unclean_digest {
                 nb_unclean_digests += 1;
             }
-            if !details.accumulative.verified {
-                nb_unverified += 1;
+            if !details.accumulative.verified && !dep.ignored {
+                if dep.in_baseline {
+                    nb_unverified_in_baseline += 1;
+                } else {
+                    nb_unverified += 1;
+                    if missing_reviewers {
+                        nb_missing_reviewers += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    let nb_with_trusted_issues = if args.fail_on_issue {
+        deps.iter()
+            .filter(|dep| !dep.in_baseline && !dep.ignored)
+            .filter(|dep| has_trusted_issues(dep))
+            .count()
+    } else {
+        0
+    };
+
+    let mut nb_over_geiger = 0;
+    if let Some(max_geiger) = args.max_geiger {
+        let mut nb_unknown_geiger = 0;
+        for dep in deps.iter().filter(|dep| !dep.in_baseline && !dep.ignored) {
+            let details = match dep.details() {
+                Some(details) => details,
+                None => continue,
+            };
+            match details.accumulative.geiger_count {
+                Some(geiger_count) if geiger_count > max_geiger => {
+                    nb_over_geiger += 1;
+                    term.eprint(
+                        format_args!(
+                            "Crate {} {} has a geiger count of {}\n",
+                            &dep.info.id.name(),
+                            &dep.info.id.version(),
+                            geiger_count
+                        ),
+                        ::term::color::RED,
+                    )?;
+                }
+                Some(_) => {}
+                None => {
+                    nb_unknown_geiger += 1;
+                    term.eprint(
+                        format_args!(
+                            "Crate {} {} has an unknown geiger count\n",
+                            &dep.info.id.name(),
+                            &dep.info.id.version()
+                        ),
+                        ::term::color::YELLOW,
+                    )?;
+                }
+            }
+        }
+        if nb_unknown_geiger > 0 {
+            eprintln!(
+                "{} package{} with unknown geiger count, not checked against `--max-geiger {}`.",
+                nb_unknown_geiger,
+                if nb_unknown_geiger > 1 { "s" } else { "" },
+                max_geiger,
+            );
+        }
+    }
+
+    let mut nb_low_downloads = 0;
+    if let Some(min_downloads) = args.min_downloads {
+        let mut nb_unknown_downloads = 0;
+        for dep in deps.iter().filter(|dep| !dep.in_baseline && !dep.ignored) {
+            let details = match dep.details() {
+                Some(details) => details,
+                None => continue,
+            };
+            match details.version_downloads {
+                Some(ref downloads) if downloads.total < min_downloads => {
+                    if args.fail_on_low_downloads {
+                        nb_low_downloads += 1;
+                    }
+                    term.eprint(
+                        format_args!(
+                            "Crate {} {} has only {} downloads\n",
+                            &dep.info.id.name(),
+                            &dep.info.id.version(),
+                            downloads.total
+                        ),
+                        if args.fail_on_low_downloads {
+                            ::term::color::RED
+                        } else {
+                            ::term::color::YELLOW
+                        },
+                    )?;
+                }
+                Some(_) => {}
+                None => {
+                    nb_unknown_downloads += 1;
+                    term.eprint(
+                        format_args!(
+                            "Crate {} {} has an unknown download count\n",
+                            &dep.info.id.name(),
+                            &dep.info.id.version()
+                        ),
+                        ::term::color::YELLOW,
+                    )?;
+                }
             }
         }
+        if nb_unknown_downloads > 0 {
+            eprintln!(
+                "{} package{} with unknown download count, not checked against `--min-downloads {}`.",
+                nb_unknown_downloads,
+                if nb_unknown_downloads > 1 { "s" } else { "" },
+                min_downloads,
+            );
+        }
+    }
+
+    let mut nb_over_budget = 0;
+    if let Some(ref budget) = budget {
+        let violations = budget.check(&deps, &mut term)?;
+        if violations.nb_unknown > 0 {
+            eprintln!(
+                "{} unknown value{}, not checked against the budget.",
+                violations.nb_unknown,
+                if violations.nb_unknown > 1 { "s" } else { "" },
+            );
+        }
+        nb_over_budget = violations.nb_failed;
+    }
+
+    if nb_pruned > 0 {
+        eprintln!(
+            "{} package{} below packages without trusted reviews not verified (`--prune-unreviewed`).",
+            nb_pruned,
+            if nb_pruned > 1 { "s" } else { "" },
+        );
+    }
+
+    if nb_unverified_in_baseline > 0 {
+        eprintln!(
+            "{} unverified package{} already present at the baseline, not failing on them.",
+            nb_unverified_in_baseline,
+            if nb_unverified_in_baseline > 1 {
+                "s"
+            } else {
+                ""
+            },
+        );
+    }
+
+    for dep in &deps {
+        if dep.details().filter(|d| d.redownloaded).is_some() {
+            term.eprint(
+                format_args!(
+                    "Re-extracted unclean crate {} {}\n",
+                    &dep.info.id.name(),
+                    &dep.info.id.version()
+                ),
+                ::term::color::YELLOW,
+            )?;
+        }
     }
 
     if nb_unclean_digests > 0 {
-        println!(
+        eprintln!(
             "{} unclean package{} detected. Use `cargo crev clean <crate>` to wipe the local source.",
             nb_unclean_digests,
             if nb_unclean_digests > 1 { "s" } else { "" },
         );
-        for dep in deps {
+        for dep in &deps {
             if dep.is_digest_unclean() {
                 term.eprint(
                     format_args!(
@@ -351,9 +791,143 @@ pub fn verify_deps(args: CrateVerify) -> Result<CommandExitStatus> {
         }
     }
 
-    Ok(if nb_unverified == 0 {
+    if nb_unverified > 0 {
+        eprintln!(
+            "Verification failed: {} unverified package{}.",
+            nb_unverified,
+            if nb_unverified > 1 { "s" } else { "" },
+        );
+    }
+    if nb_missing_reviewers > 0 {
+        eprintln!(
+            "{} of them need{} reviews from more independent reviewers (`--require-reviewers`).",
+            nb_missing_reviewers,
+            if nb_missing_reviewers > 1 { "" } else { "s" },
+        );
+    }
+    if nb_with_trusted_issues > 0 {
+        eprintln!(
+            "Verification failed: {} package{} with issues reported by trusted reviewers (`--fail-on-issue`).",
+            nb_with_trusted_issues,
+            if nb_with_trusted_issues > 1 { "s" } else { "" },
+        );
+    }
+
+    if nb_over_geiger > 0 {
+        eprintln!(
+            "Verification failed: {} package{} over the geiger count budget (`--max-geiger`).",
+            nb_over_geiger,
+            if nb_over_geiger > 1 { "s" } else { "" },
+        );
+    }
+
+    if nb_low_downloads > 0 {
+        eprintln!(
+            "Verification failed: {} package{} under the download count minimum (`--fail-on-low-downloads`).",
+            nb_low_downloads,
+            if nb_low_downloads > 1 { "s" } else { "" },
+        );
+    }
+
+    if nb_over_budget > 0 {
+        eprintln!(
+            "Verification failed: {} package{} over the budget (`--budget`).",
+            nb_over_budget,
+            if nb_over_budget > 1 { "s" } else { "" },
+        );
+    }
+
+    if args.review_missing_interactive {
+        review_missing::review_unverified(&deps, &args, &term)?;
+    }
+    if args.review_diffs_interactive {
+        review_missing::review_unverified_diffs(&deps, &args, &term)?;
+    }
+
+    let passed = nb_unverified == 0
+        && nb_with_trusted_issues == 0
+        && nb_over_geiger == 0
+        && nb_low_downloads == 0
+        && nb_over_budget == 0;
+
+    if let Some(ref url) = args.report_to {
+        // the report is informative; failing to send it doesn't fail the verification
+        if let Err(e) = report::post_report(url, &deps, passed, &args) {
+            eprintln!("Failed to report the results to {}: {}", url, e);
+        }
+    }
+
+    let mut hook_failed = false;
+    if let Some(ref cmd) = args.post_verify_hook {
+        if let Err(e) = report::run_post_verify_hook(cmd, &deps, passed, &args) {
+            eprintln!("Post-verify hook failed: {}", e);
+            hook_failed = args.hook_affects_exit;
+        }
+    }
+
+    Ok(if passed && !hook_failed {
         CommandExitStatus::Success
     } else {
         CommandExitStatus::VerificationFailed
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, s: &str) -> bool {
+        wildcard_matches(pattern.as_bytes(), s.as_bytes())
+    }
+
+    #[test]
+    fn wildcard_matches_exact_names() {
+        assert!(matches("serde", "serde"));
+        assert!(!matches("serde", "serde_json"));
+        assert!(!matches("serde_json", "serde"));
+    }
+
+    #[test]
+    fn wildcard_matches_stars_anywhere() {
+        assert!(matches("*-sys", "openssl-sys"));
+        assert!(!matches("*-sys", "openssl"));
+        assert!(matches("winapi*", "winapi"));
+        assert!(matches("winapi*", "winapi-x86_64-pc-windows-gnu"));
+        assert!(matches("tokio-*-util", "tokio-io-util"));
+        assert!(!matches("tokio-*-util", "tokio-util"));
+        assert!(matches("*", "anything"));
+        assert!(matches("ser?e", "serde"));
+        assert!(!matches("ser?e", "sere"));
+    }
+
+    #[test]
+    fn wildcard_matches_empty() {
+        assert!(matches("", ""));
+        assert!(!matches("", "serde"));
+        assert!(matches("*", ""));
+        assert!(!matches("?", ""));
+    }
+
+    #[test]
+    fn ignore_pattern_splits_name_and_version_requirement() -> Result<()> {
+        let v = |s: &str| Version::parse(s).unwrap();
+
+        let pattern: IgnorePattern = "winapi*".parse()?;
+        assert_eq!(pattern.name, "winapi*");
+        assert!(pattern.version_req.is_none());
+        assert!(pattern.matches("winapi-util", &v("0.1.2")));
+
+        for s in &["rand = \"0.6\"", "rand=0.6", "rand@0.6", " rand @ 0.6 "] {
+            let pattern: IgnorePattern = s.parse()?;
+            assert_eq!(pattern.name, "rand");
+            assert!(pattern.matches("rand", &v("0.6.5")));
+            assert!(!pattern.matches("rand", &v("0.7.0")));
+            assert!(!pattern.matches("rand_core", &v("0.6.5")));
+        }
+
+        assert!("".parse::<IgnorePattern>().is_err());
+        assert!("= 1.0".parse::<IgnorePattern>().is_err());
+        assert!("rand = not-a-version".parse::<IgnorePattern>().is_err());
+        Ok(())
+    }
+}
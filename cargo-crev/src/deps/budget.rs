@@ -0,0 +1,132 @@
+// Thresholds of `crate verify --budget`, kept in a file under version control
+use crate::{deps::CrateStats, prelude::*, term};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Thresholds for the metrics of each crate, read from a TOML file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Budget {
+    /// Most `unsafe` lines (geiger count), including dependencies with `--recursive`
+    pub max_geiger: Option<Limit>,
+    /// Fewest downloads (all versions together)
+    pub min_downloads: Option<Limit>,
+    /// Fewest independent trusted reviewers of the version used
+    pub min_reviewers: Option<Limit>,
+    /// Most issues reported by trusted reviewers
+    pub max_issues: Option<Limit>,
+    /// Most lines of Rust code
+    pub max_loc: Option<Limit>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Limit {
+    pub limit: u64,
+    /// Fail the verification when exceeded, instead of only warning
+    #[serde(default = "default_fail")]
+    pub fail: bool,
+}
+
+fn default_fail() -> bool {
+    true
+}
+
+/// Budget lines exceeded by the crates
+#[derive(Debug, Default)]
+pub struct BudgetViolations {
+    /// Packages exceeding at least one limit with `fail = true`
+    pub nb_failed: usize,
+    /// Values not known, so not checked
+    pub nb_unknown: usize,
+}
+
+impl Budget {
+    pub fn read_from(path: &Path) -> Result<Self> {
+        let txt = crev_common::read_file_to_string(path)
+            .with_context(|_| format!("Can't read budget {}", path.display()))?;
+        Ok(toml::from_str(&txt).with_context(|_| format!("Can't parse {}", path.display()))?)
+    }
+
+    /// Check each crate against the limits, printing each one exceeded
+    pub fn check(&self, deps: &[CrateStats], term: &mut term::Term) -> Result<BudgetViolations> {
+        let mut violations = BudgetViolations::default();
+        for dep in deps.iter().filter(|dep| !dep.in_baseline && !dep.ignored) {
+            let details = match dep.details() {
+                Some(details) => details,
+                None => continue,
+            };
+            let figures = &details.accumulative;
+            let checks = [
+                ("max-geiger", &self.max_geiger, figures.geiger_count, true),
+                (
+                    "min-downloads",
+                    &self.min_downloads,
+                    details.version_downloads.as_ref().map(|d| d.total),
+                    false,
+                ),
+                (
+                    "min-reviewers",
+                    &self.min_reviewers,
+                    Some(details.independent_reviewers),
+                    false,
+                ),
+                (
+                    "max-issues",
+                    &self.max_issues,
+                    Some(figures.trusted_issues.count),
+                    true,
+                ),
+                (
+                    "max-loc",
+                    &self.max_loc,
+                    figures.loc.map(|loc| loc as u64),
+                    true,
+                ),
+            ];
+
+            let mut failed = false;
+            for (name, limit, value, is_max) in checks.iter() {
+                let limit = match limit {
+                    Some(limit) => limit,
+                    None => continue,
+                };
+                let value = match value {
+                    Some(value) => *value,
+                    None => {
+                        violations.nb_unknown += 1;
+                        continue;
+                    }
+                };
+                let exceeded = if *is_max {
+                    value > limit.limit
+                } else {
+                    value < limit.limit
+                };
+                if !exceeded {
+                    continue;
+                }
+                failed |= limit.fail;
+                term.eprint(
+                    format_args!(
+                        "Crate {} {} is outside the budget `{} = {}`: {}\n",
+                        dep.info.id.name(),
+                        dep.info.id.version(),
+                        name,
+                        limit.limit,
+                        value
+                    ),
+                    if limit.fail {
+                        ::term::color::RED
+                    } else {
+                        ::term::color::YELLOW
+                    },
+                )?;
+            }
+            if failed {
+                violations.nb_failed += 1;
+            }
+        }
+        Ok(violations)
+    }
+}
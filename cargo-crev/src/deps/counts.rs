@@ -0,0 +1,57 @@
+// Only the totals of `crate verify`, eg. for a README badge
+use crate::deps::{has_trusted_issues, CrateStats};
+use serde_json::json;
+
+#[derive(Debug, Default)]
+pub struct VerifyCounts {
+    pub total: usize,
+    pub verified: usize,
+    pub unverified: usize,
+    pub unclean: usize,
+    pub with_issues: usize,
+}
+
+impl VerifyCounts {
+    pub fn new(deps: &[CrateStats]) -> Self {
+        let mut counts = Self::default();
+        for stats in deps {
+            counts.total += 1;
+            let details = match stats.details() {
+                Some(details) => details,
+                None => continue,
+            };
+            if details.accumulative.verified {
+                counts.verified += 1;
+            } else if !stats.ignored {
+                counts.unverified += 1;
+            }
+            if details.unclean_digest {
+                counts.unclean += 1;
+            }
+            if has_trusted_issues(stats) {
+                counts.with_issues += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn print_text(&self) {
+        println!(
+            "total={} verified={} unverified={} unclean={} with-issues={}",
+            self.total, self.verified, self.unverified, self.unclean, self.with_issues
+        );
+    }
+
+    pub fn print_json(&self) {
+        println!(
+            "{}",
+            json!({
+                "total": self.total,
+                "verified": self.verified,
+                "unverified": self.unverified,
+                "unclean": self.unclean,
+                "with-issues": self.with_issues,
+            })
+        );
+    }
+}
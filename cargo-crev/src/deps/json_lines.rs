@@ -0,0 +1,84 @@
+// Streaming `crate verify` results, one JSON object per line
+use crate::{
+    deps::{CountWithTotal, CrateStats},
+    prelude::*,
+};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+fn count_with_total(c: &CountWithTotal) -> Value {
+    json!({ "count": c.count, "total": c.total })
+}
+
+/// JSON object with the results for a crate
+pub fn stats_to_json(stats: &CrateStats) -> Value {
+    let mut value = json!({
+        "name": stats.info.id.name().as_str(),
+        "version": stats.info.id.version().to_string(),
+        "in-baseline": stats.in_baseline,
+        "ignored": stats.ignored,
+    });
+
+    match stats.details {
+        Err(ref e) => value["error"] = json!(e.to_string()),
+        Ok(None) => {}
+        Ok(Some(ref details)) => {
+            let accumulative = &details.accumulative;
+            value["details"] = json!({
+                "digest": details.digest.to_string(),
+                "digest-type": details.digest_type,
+                "trust": accumulative.trust.to_string(),
+                "verified": accumulative.verified,
+                "reviews": count_with_total(&details.version_reviews),
+                "downloads": details.version_downloads.as_ref().map(count_with_total),
+                "owners": details.known_owners.as_ref().map(count_with_total),
+                "issues": count_with_total(&accumulative.trusted_issues),
+                "loc": accumulative.loc,
+                "geiger-count": accumulative.geiger_count,
+                "has-custom-build": accumulative.has_custom_build,
+                "unclean-digest": details.unclean_digest,
+                "latest-trusted-version": details
+                    .latest_trusted_version
+                    .as_ref()
+                    .map(ToString::to_string),
+                "latest-trusted-review-date": details
+                    .latest_trusted_review_date
+                    .as_ref()
+                    .map(ToString::to_string),
+            });
+        }
+    }
+
+    value
+}
+
+/// Appends `CrateStats` to a file as they are produced, so that long scans can be monitored
+pub struct JsonLinesWriter {
+    path: PathBuf,
+    file: fs::File,
+}
+
+impl JsonLinesWriter {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format_err!("Can't open {}: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_owned(),
+            file,
+        })
+    }
+
+    pub fn write(&mut self, stats: &CrateStats) -> Result<()> {
+        writeln!(self.file, "{}", stats_to_json(stats))
+            .and_then(|_| self.file.flush())
+            .map_err(|e| format_err!("Can't write to {}: {}", self.path.display(), e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+// Reporting `crate verify` results as a GitHub-flavored Markdown table,
+// eg. to paste into a pull request
+use crate::deps::{CrateDetails, CrateStats};
+use crev_lib::VerificationStatus;
+
+/// Crate names and versions as code, so `_` etc. aren't taken as formatting
+fn code(s: &str) -> String {
+    // `|` would end the table cell, even in code
+    format!("`{}`", s.replace('|', "\\|"))
+}
+
+fn status(stats: &CrateStats, details: &CrateDetails) -> &'static str {
+    if stats.ignored {
+        return "➖ ignored";
+    }
+    match details.accumulative.trust {
+        VerificationStatus::Verified => "✅ pass",
+        VerificationStatus::Insufficient => "❔ none",
+        VerificationStatus::Negative => "❌ warn",
+    }
+}
+
+fn opt_to_string<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "?".into())
+}
+
+fn print_row(stats: &CrateStats, details: &CrateDetails) {
+    let mut flags = vec![];
+    if stats.has_custom_build() == Some(true) {
+        flags.push("custom build");
+    }
+    if details.owners_changed == Some(true) {
+        flags.push("owners changed");
+    }
+    if details.unclean_digest {
+        flags.push("unclean");
+    }
+
+    println!(
+        "| {} | {} | {} | {}/{} | {} | {}/{} | {} | {} | {} | {} |",
+        status(stats, details),
+        code(&stats.info.id.name()),
+        code(&stats.info.id.version().to_string()),
+        details.version_reviews.count,
+        details.version_reviews.total,
+        opt_to_string(details.version_downloads.as_ref().map(|d| d.count)),
+        details.accumulative.trusted_issues.count,
+        details.accumulative.trusted_issues.total,
+        opt_to_string(details.accumulative.loc),
+        opt_to_string(details.accumulative.geiger_count),
+        flags.join(", "),
+        details
+            .latest_trusted_version
+            .as_ref()
+            .map(|version| code(&version.to_string()))
+            .unwrap_or_default(),
+    );
+}
+
+/// Print a Markdown table with a row per crate, and a summary line
+pub fn print_markdown(deps: &[CrateStats]) {
+    println!(
+        "| status | crate | version | reviews | downloads | issues | lines | geiger | flags | latest trusted |"
+    );
+    println!("|---|---|---|--:|--:|--:|--:|--:|---|---|");
+
+    let mut nb_verified = 0;
+    let mut nb_unverified = 0;
+    let mut nb_unclean = 0;
+    for stats in deps {
+        let details = match stats.details() {
+            Some(details) => details,
+            None => continue,
+        };
+        print_row(stats, details);
+        if details.accumulative.verified {
+            nb_verified += 1;
+        } else if !stats.ignored {
+            nb_unverified += 1;
+        }
+        if details.unclean_digest {
+            nb_unclean += 1;
+        }
+    }
+
+    println!();
+    println!(
+        "**{}** verified, **{}** unverified, **{}** unclean",
+        nb_verified, nb_unverified, nb_unclean
+    );
+}
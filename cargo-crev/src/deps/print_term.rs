@@ -3,6 +3,7 @@
 
 use super::*;
 use crate::term::{self, *};
+use std::collections::BTreeMap;
 
 fn pad_left_manually(s: String, width: usize) -> String {
     if s.len() <= width {
@@ -15,7 +16,87 @@ fn pad_left_manually(s: String, width: usize) -> String {
     }
 }
 
-pub fn print_header(_term: &mut Term, verbose: bool) {
+/// Widths of the columns adapting to the width of the terminal
+struct CrateColumns {
+    name: usize,
+    version: usize,
+    // truncate names longer than `name`
+    truncate: bool,
+}
+
+const NAME_WIDTH: usize = 20;
+const MIN_NAME_WIDTH: usize = 10;
+const MAX_NAME_WIDTH: usize = 40;
+const VERSION_WIDTH: usize = 15;
+const NARROW_VERSION_WIDTH: usize = 10;
+
+impl CrateColumns {
+    fn new(term: &Term, verbose: bool, show_review_date: bool, show_reviewers: bool) -> Self {
+        let width = match term.width {
+            Some(width) => width,
+            None => {
+                return CrateColumns {
+                    name: NAME_WIDTH,
+                    version: VERSION_WIDTH,
+                    truncate: false,
+                }
+            }
+        };
+
+        // everything before the crate name column
+        let mut fixed = 64;
+        if verbose {
+            fixed += 44;
+        }
+        if show_reviewers {
+            fixed += 6;
+        }
+        if show_review_date {
+            fixed += 11;
+        }
+
+        let available = width.saturating_sub(fixed);
+        // name, version and latest trusted version, with a space before each
+        let version = if available >= 3 + NAME_WIDTH + 2 * VERSION_WIDTH {
+            VERSION_WIDTH
+        } else {
+            NARROW_VERSION_WIDTH
+        };
+        // wider names are rare, don't waste the space
+        let name = std::cmp::min(available.saturating_sub(3 + 2 * version), MAX_NAME_WIDTH);
+        CrateColumns {
+            // keep names readable, even if the table overflows
+            name: std::cmp::max(name, MIN_NAME_WIDTH),
+            version,
+            truncate: true,
+        }
+    }
+
+    fn name(&self, name: &str) -> String {
+        if self.truncate && name.chars().count() > self.name {
+            let mut name: String = name.chars().take(self.name - 1).collect();
+            name.push('…');
+            name
+        } else {
+            name.to_owned()
+        }
+    }
+}
+
+pub fn print_header(
+    term: &mut Term,
+    verbose: bool,
+    show_review_date: bool,
+    show_reviewers: bool,
+    recursive: bool,
+    accumulate: bool,
+) {
+    let columns = CrateColumns::new(term, verbose, show_review_date, show_reviewers);
+    if recursive && accumulate {
+        eprintln!("issues, lines, geiger and flags include all the dependencies of each crate");
+    } else if !accumulate {
+        eprintln!("issues, lines, geiger and flags of each crate alone (`--no-accumulate`)");
+    }
     if verbose {
         eprint!("{:43} ", "digest");
     }
@@ -23,7 +104,20 @@ pub fn print_header(_term: &mut Term, verbose: bool) {
         "{:6} {:8} {:^15} {:6} {:6} {:6} {:6} {:4}",
         "status", "reviews", "downloads", "owner", "issues", "lines", "geiger", "flgs"
     );
-    eprintln!(" {:<20} {:<15} {:<15}", "crate", "version", "latest_t");
+    if show_reviewers {
+        eprint!(" {:>5}", "rvwrs");
+    }
+    if show_review_date {
+        eprint!(" {:10}", "reviewed");
+    }
+    eprintln!(
+        " {:<name$} {:<version$} {:<version$}",
+        "crate",
+        "version",
+        "latest_t",
+        name = columns.name,
+        version = columns.version
+    );
 }
 
 #[allow(clippy::collapsible_if)]
@@ -32,16 +126,22 @@ pub fn print_details(
     term: &mut Term,
     verbose: bool,
     recursive_mode: bool,
+    ignored: bool,
 ) -> Result<()> {
     if verbose {
         print!("{:43} ", cdep.digest);
     }
-    term.print(
-        format_args!("{:6}", cdep.accumulative.trust),
-        term::verification_status_color(cdep.accumulative.trust),
-    )?;
+    if ignored {
+        // one char wider than the column; takes the place of the separator
+        term.print(format_args!("{:7}", "ignored"), None)?;
+    } else {
+        term.print(
+            format_args!("{:6} ", cdep.accumulative.trust),
+            term::verification_status_color(cdep.accumulative.trust),
+        )?;
+    }
     print!(
-        " {:2} {:2}",
+        "{:2} {:2}",
         cdep.version_reviews.count, cdep.version_reviews.total
     );
     if let Some(downloads) = &cdep.version_downloads {
@@ -86,9 +186,15 @@ pub fn print_details(
         }
     }
 
+    // without `--recursive` (or with `--no-accumulate`), figures of the crate alone
+    let figures = if recursive_mode {
+        &cdep.accumulative
+    } else {
+        &cdep.accumulative_own
+    };
     term.print(
-        format_args!("{:4}", cdep.accumulative.trusted_issues.count),
-        if cdep.accumulative.trusted_issues.count > 0 {
+        format_args!("{:4}", figures.trusted_issues.count),
+        if figures.trusted_issues.count > 0 {
             Some(::term::color::RED)
         } else {
             None
@@ -96,14 +202,14 @@ pub fn print_details(
     )?;
     print!("/");
     term.print(
-        format_args!("{:<2}", cdep.accumulative.trusted_issues.total),
-        if cdep.accumulative.trusted_issues.total > 0 {
+        format_args!("{:<2}", figures.trusted_issues.total),
+        if figures.trusted_issues.total > 0 {
             Some(::term::color::YELLOW)
         } else {
             None
         },
     )?;
-    match cdep.accumulative.loc {
+    match figures.loc {
         Some(loc) => print!(" {:>6}", loc),
         None => print!(" {:>6}", "err"),
     }
@@ -111,48 +217,96 @@ pub fn print_details(
     Ok(())
 }
 
-fn print_stats_crate_id(stats: &CrateStats, _term: &mut Term) {
+fn print_stats_crate_id(stats: &CrateStats, columns: &CrateColumns) {
     print!(
-        " {:<20} {:<15}",
-        stats.info.id.name(),
-        pad_left_manually(stats.info.id.version().to_string(), 15)
+        " {:<width$} {}",
+        columns.name(&stats.info.id.name()),
+        pad_left_manually(stats.info.id.version().to_string(), columns.version),
+        width = columns.name
     );
 }
 
+fn print_review_date(
+    details: &CrateDetails,
+    term: &mut Term,
+    stale_after_days: Option<i64>,
+) -> Result<()> {
+    match details.latest_trusted_review_date {
+        Some(ref date) => {
+            let is_stale = stale_after_days
+                .map(|days| (crev_common::now() - *date).num_days() > days)
+                .unwrap_or(false);
+            term.print(
+                format_args!(" {:10}", date.format("%Y-%m-%d").to_string()),
+                if is_stale {
+                    Some(::term::color::RED)
+                } else {
+                    None
+                },
+            )?;
+        }
+        None => print!(" {:10}", "-"),
+    }
+    Ok(())
+}
+
 pub fn print_dep(
     stats: &CrateStats,
     term: &mut Term,
     verbose: bool,
     recursive_mode: bool,
+    show_review_date: bool,
+    stale_after_days: Option<i64>,
+    require_reviewers: Option<u64>,
 ) -> Result<()> {
+    let columns = CrateColumns::new(term, verbose, show_review_date, require_reviewers.is_some());
     match &stats.details {
         Err(_) => {
-            print_stats_crate_id(stats, term);
+            print_stats_crate_id(stats, &columns);
             println!(" -- computation failed");
         }
         Ok(None) => { /* just skip */ }
         Ok(Some(details)) => {
-            print_details(&details, term, verbose, recursive_mode)?;
-            match details.accumulative.geiger_count {
+            print_details(&details, term, verbose, recursive_mode, stats.ignored)?;
+            let figures = if recursive_mode {
+                &details.accumulative
+            } else {
+                &details.accumulative_own
+            };
+            match figures.geiger_count {
                 Some(geiger_count) => print!(" {:>7}", geiger_count),
                 None => print!(" {:>7}", "err"),
             }
+            let custom_build_flag = if figures.has_custom_build { "CB" } else { "" };
+            let owners_changed_flag = if details.owners_changed == Some(true) {
+                "OC"
+            } else {
+                ""
+            };
             term.print(
-                format_args!(
-                    " {:4}",
-                    if let Some(has_custom_build) = stats.has_custom_build() {
-                        if has_custom_build {
-                            "CB"
-                        } else {
-                            ""
-                        }
-                    } else {
-                        "?"
-                    }
-                ),
+                format_args!(" {}", custom_build_flag),
                 ::term::color::YELLOW,
             )?;
-            print_stats_crate_id(stats, term);
+            term.print(format_args!("{}", owners_changed_flag), ::term::color::RED)?;
+            print!(
+                "{:1$}",
+                "",
+                4usize.saturating_sub(custom_build_flag.len() + owners_changed_flag.len())
+            );
+            if let Some(require_reviewers) = require_reviewers {
+                term.print(
+                    format_args!(" {:>5}", details.independent_reviewers),
+                    if details.independent_reviewers < require_reviewers {
+                        Some(::term::color::RED)
+                    } else {
+                        None
+                    },
+                )?;
+            }
+            if show_review_date {
+                print_review_date(details, term, stale_after_days)?;
+            }
+            print_stats_crate_id(stats, &columns);
             print!(
                 " {}",
                 latest_trusted_version_string(
@@ -165,3 +319,152 @@ pub fn print_dep(
     }
     Ok(())
 }
+
+/// Print owner logins of a crate, highlighting known owners
+pub fn print_owners(
+    stats: &CrateStats,
+    term: &mut Term,
+    known_owners: &HashSet<String>,
+) -> Result<()> {
+    let owner_list = match stats.details().and_then(|d| d.owner_list.as_ref()) {
+        Some(owner_list) => owner_list,
+        None => return Ok(()),
+    };
+    let mut owner_list: Vec<_> = owner_list.iter().collect();
+    owner_list.sort();
+
+    print!("{:>8}", "owners:");
+    for owner in owner_list {
+        print!(" ");
+        term.print(
+            format_args!("{}", owner),
+            if known_owners.contains(owner.as_str()) {
+                Some(::term::color::GREEN)
+            } else {
+                None
+            },
+        )?;
+    }
+    println!();
+    Ok(())
+}
+
+/// Print a warning line for each advisory of the crate from trusted reviewers
+pub fn print_advisories(stats: &CrateStats, term: &mut Term) -> Result<()> {
+    let advisories = match stats.details() {
+        Some(details) => &details.trusted_advisories,
+        None => return Ok(()),
+    };
+    for advisory in advisories {
+        let ids = if advisory.ids.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", advisory.ids.join(", "))
+        };
+        print!("{:>8} ", "warning:");
+        term.print(
+            format_args!(
+                "{} severity advisory{} by {} ({})",
+                advisory.severity, ids, advisory.author.id, advisory.author.url.url
+            ),
+            ::term::color::RED,
+        )?;
+        println!();
+        let comment = advisory.comment.trim();
+        if !comment.is_empty() {
+            for line in comment.lines() {
+                println!("{:>8} {}", "", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the ids of open issues of the crate from trusted reviewers, if any
+pub fn print_issues(stats: &CrateStats, term: &mut Term) -> Result<()> {
+    let ids = match stats.details() {
+        Some(details) if !details.trusted_issue_ids.is_empty() => &details.trusted_issue_ids,
+        _ => return Ok(()),
+    };
+    print!("{:>8} ", "issues:");
+    term.print(format_args!("{}", ids.join(", ")), ::term::color::RED)?;
+    println!();
+    Ok(())
+}
+
+/// Print the proof repositories of trusted reviewers of the crate, if any
+pub fn print_review_sources(stats: &CrateStats) {
+    let reviewers = match stats.details() {
+        Some(details) if !details.trusted_reviewers.is_empty() => &details.trusted_reviewers,
+        _ => return,
+    };
+    let mut urls: Vec<_> = reviewers
+        .iter()
+        .map(|reviewer| reviewer.url.url.as_str())
+        .collect();
+    urls.sort();
+    urls.dedup();
+
+    println!("{:>8} {}", "reviews:", urls.join(" "));
+}
+
+pub fn print_timings(deps: &[CrateStats]) {
+    let mut deps: Vec<_> = deps.iter().collect();
+    deps.sort_by(|a, b| a.timings.total().cmp(&b.timings.total()).reverse());
+
+    eprintln!(
+        "{:>8} {:>8} {:>8} {:>8} {:>8} {:<20} {:<15}",
+        "total", "download", "digest", "geiger", "lines", "crate", "version"
+    );
+    for stats in deps {
+        let t = &stats.timings;
+        eprintln!(
+            "{:>7.2}s {:>7.2}s {:>7.2}s {:>7.2}s {:>7.2}s {:<20} {:<15}",
+            t.total().as_secs_f64(),
+            t.download.as_secs_f64(),
+            t.digest.as_secs_f64(),
+            t.geiger.as_secs_f64(),
+            t.loc.as_secs_f64(),
+            stats.info.id.name(),
+            stats.info.id.version().to_string(),
+        );
+    }
+}
+
+/// Print the deepest dependency chain, and the number of crates at each depth
+pub fn print_dependency_depths(depths: &HashMap<PackageId, (u64, Option<PackageId>)>) {
+    let deepest = depths
+        .iter()
+        .max_by(|(a_id, (a_depth, _)), (b_id, (b_depth, _))| {
+            // on ties, the first by name, to keep the output stable
+            a_depth.cmp(b_depth).then_with(|| b_id.cmp(a_id))
+        })
+        .map(|(pkg_id, _)| *pkg_id);
+    let deepest = match deepest {
+        Some(deepest) => deepest,
+        None => return,
+    };
+
+    let mut path = vec![deepest];
+    while let Some((_, Some(parent))) = depths.get(path.last().unwrap()) {
+        path.push(*parent);
+    }
+    path.reverse();
+    eprintln!("Longest dependency path ({}):", path.len() - 1);
+    eprintln!(
+        "  {}",
+        path.iter()
+            .map(|pkg_id| format!("{} {}", pkg_id.name(), pkg_id.version()))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    );
+
+    let mut count_by_depth = BTreeMap::new();
+    for (depth, _) in depths.values() {
+        *count_by_depth.entry(*depth).or_insert(0) += 1;
+    }
+    eprintln!("Crates at each depth:");
+    for (depth, count) in count_by_depth {
+        eprintln!("{:>5}: {}", depth, count);
+    }
+}
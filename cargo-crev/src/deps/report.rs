@@ -0,0 +1,70 @@
+// Sending `crate verify` results to a dashboard collecting them, or to a hook command
+use crate::{
+    crates_io,
+    deps::{json_lines::stats_to_json, CrateStats},
+    opts::CrateVerify,
+    prelude::*,
+    repo::Repo,
+};
+use serde_json::json;
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// The results, with the project name and commit, as a JSON object
+fn results_json(
+    deps: &[CrateStats],
+    passed: bool,
+    args: &CrateVerify,
+) -> Result<serde_json::Value> {
+    let repo = Repo::auto_open_cwd(args.common.cargo_opts.clone())?;
+    Ok(json!({
+        "project": repo.name(),
+        "commit": repo.get_head_commit(),
+        "passed": passed,
+        "crates": deps.iter().map(stats_to_json).collect::<Vec<_>>(),
+    }))
+}
+
+/// POST the results as a JSON object to `url`
+pub fn post_report(url: &str, deps: &[CrateStats], passed: bool, args: &CrateVerify) -> Result<()> {
+    let report = results_json(deps, passed, args)?;
+
+    crates_io::http_client()?
+        .post(url)
+        .json(&report)
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Run the shell command `cmd` with the results as JSON on its stdin
+///
+/// The command gets `passed` or `failed` as its first argument.
+pub fn run_post_verify_hook(
+    cmd: &str,
+    deps: &[CrateStats],
+    passed: bool,
+    args: &CrateVerify,
+) -> Result<()> {
+    let results = serde_json::to_vec(&results_json(deps, passed, args)?)?;
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .arg("sh")
+        .arg(if passed { "passed" } else { "failed" })
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format_err!("Can't run `{}`: {}", cmd, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // the hook may not read its input at all
+        let _ = stdin.write_all(&results);
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("`{}` failed with {}", cmd, status);
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+// Offering to review the crates that failed the verification
+use crate::{
+    deps::CrateStats,
+    opts::{self, CommonProofCreate, CrateSelector, CrateVerify},
+    prelude::*,
+    review::create_review_proof,
+    shared::{are_we_called_from_goto_shell, goto_crate_src_and_wait, run_diff},
+    term::Term,
+};
+use crev_lib::TrustProofType;
+
+fn is_unverified(dep: &CrateStats) -> bool {
+    match dep.details() {
+        Some(details) => !details.accumulative.verified && !dep.ignored && !dep.in_baseline,
+        None => false,
+    }
+}
+
+/// Ask about each unverified crate, reviewing the ones the user picks
+///
+/// Opens a `goto` shell in the crate source, then creates a review proof
+/// once it exits. Does nothing if stdin is not a terminal.
+pub fn review_unverified(deps: &[CrateStats], args: &CrateVerify, term: &Term) -> Result<()> {
+    if !term.stdin_is_tty {
+        return Ok(());
+    }
+    if are_we_called_from_goto_shell().is_some() {
+        bail!("Can't review crates from inside a `cargo crev goto` shell");
+    }
+
+    let unverified: Vec<_> = deps.iter().filter(|dep| is_unverified(dep)).collect();
+    for (i, dep) in unverified.iter().enumerate() {
+        let name = dep.info.id.name();
+        let version = dep.info.id.version();
+        let msg = format!(
+            "[{}/{}] Review {} {}? (y/n) ",
+            i + 1,
+            unverified.len(),
+            name,
+            version
+        );
+        if !crev_common::yes_or_no_was_y(&msg)? {
+            continue;
+        }
+
+        let selector = CrateSelector {
+            unrelated: false,
+            name: Some(name.to_string()),
+            version: Some(version.clone()),
+        };
+        // a failed review (eg. an aborted editor) shouldn't stop the others
+        let res = goto_crate_src_and_wait(&selector).and_then(|()| {
+            create_review_proof(
+                &selector,
+                None,
+                None,
+                TrustProofType::Trust,
+                &CommonProofCreate::default(),
+                false,
+                &None,
+                false,
+                false,
+                args.common.cargo_opts.clone(),
+            )
+        });
+        if let Err(e) = res {
+            eprintln!("Not reviewing {} {}: {}", name, version, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Ask about each unverified crate with an older trusted version, showing
+/// the diff from it, and creating a differential review if the user wants
+///
+/// Does nothing if stdin is not a terminal.
+pub fn review_unverified_diffs(deps: &[CrateStats], args: &CrateVerify, term: &Term) -> Result<()> {
+    if !term.stdin_is_tty {
+        return Ok(());
+    }
+
+    let upgraded: Vec<_> = deps
+        .iter()
+        .filter(|dep| is_unverified(dep))
+        .filter_map(|dep| {
+            let trusted = dep.details()?.latest_trusted_version.as_ref()?;
+            if trusted == dep.info.id.version() {
+                None
+            } else {
+                Some((dep, trusted))
+            }
+        })
+        .collect();
+    for (i, (dep, trusted)) in upgraded.iter().enumerate() {
+        let name = dep.info.id.name();
+        let version = dep.info.id.version();
+        let msg = format!(
+            "[{}/{}] Show diff of {} {} -> {}? (y/n) ",
+            i + 1,
+            upgraded.len(),
+            name,
+            trusted,
+            version
+        );
+        if !crev_common::yes_or_no_was_y(&msg)? {
+            continue;
+        }
+
+        let diff_args = opts::Diff {
+            src: Some((*trusted).clone()),
+            dst: Some(version.clone()),
+            requirements: args.common.requirements.clone(),
+            trust_params: args.common.trust_params.clone(),
+            stat: false,
+            diff_command: None,
+            name: name.to_string(),
+            args: vec![],
+        };
+        if let Err(e) = run_diff(&diff_args) {
+            eprintln!("Can't diff {} {}: {}", name, version, e);
+            continue;
+        }
+
+        let msg = format!(
+            "Review {} {} as a diff from {}? (y/n) ",
+            name, version, trusted
+        );
+        if !crev_common::yes_or_no_was_y(&msg)? {
+            continue;
+        }
+        let selector = CrateSelector {
+            unrelated: false,
+            name: Some(name.to_string()),
+            version: Some(version.clone()),
+        };
+        // a failed review (eg. an aborted editor) shouldn't stop the others
+        let res = create_review_proof(
+            &selector,
+            None,
+            None,
+            TrustProofType::Trust,
+            &CommonProofCreate::default(),
+            false,
+            &Some(Some((*trusted).clone())),
+            false,
+            false,
+            args.common.cargo_opts.clone(),
+        );
+        if let Err(e) = res {
+            eprintln!("Not reviewing {} {}: {}", name, version, e);
+        }
+    }
+
+    Ok(())
+}
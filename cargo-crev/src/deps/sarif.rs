@@ -0,0 +1,113 @@
+// Reporting `crate verify` results in SARIF format
+//
+// https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+use crate::{deps::CrateStats, prelude::*};
+use crev_lib::VerificationStatus;
+use serde_json::{json, Value};
+
+const SCHEMA: &str = "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0.json";
+const RULE_UNVERIFIED: &str = "crev/unverified";
+const RULE_ISSUE: &str = "crev/issue";
+
+fn result(rule_id: &str, level: &str, stats: &CrateStats, message: String) -> Value {
+    let name = stats.info.id.name();
+    let version = stats.info.id.version();
+    json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": "Cargo.lock" }
+            },
+            "logicalLocations": [{
+                "name": name.as_str(),
+                "fullyQualifiedName": format!("{} {}", name, version),
+                "kind": "package"
+            }]
+        }]
+    })
+}
+
+fn results_for(stats: &CrateStats) -> Vec<Value> {
+    let details = match stats.details() {
+        Some(details) => details,
+        None => return vec![],
+    };
+    let name = stats.info.id.name();
+    let version = stats.info.id.version();
+    let mut results = vec![];
+
+    let accumulative = &details.accumulative;
+    if !accumulative.verified && !stats.ignored {
+        let (level, reason) = match accumulative.trust {
+            VerificationStatus::Negative => ("error", "negative reviews"),
+            _ => ("warning", "not enough trusted reviews"),
+        };
+        // don't fail code scanning on what was already there at `--baseline`
+        let level = if stats.in_baseline { "note" } else { level };
+        results.push(result(
+            RULE_UNVERIFIED,
+            level,
+            stats,
+            format!("Crate {} {} is not verified: {}", name, version, reason),
+        ));
+    }
+
+    let issues = &accumulative.trusted_issues;
+    if issues.total > 0 {
+        let level = if issues.count > 0 && !stats.ignored {
+            "error"
+        } else {
+            "note"
+        };
+        results.push(result(
+            RULE_ISSUE,
+            level,
+            stats,
+            format!(
+                "Crate {} {} has {} open issue(s) reported, {} of them by trusted reviewers",
+                name, version, issues.total, issues.count
+            ),
+        ));
+    }
+
+    results
+}
+
+/// Print a SARIF 2.1.0 log with one result per problem found
+pub fn print_sarif(deps: &[CrateStats]) -> Result<()> {
+    let results: Vec<Value> = deps.iter().flat_map(results_for).collect();
+
+    let log = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "cargo-crev",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/crev-dev/cargo-crev",
+                    "rules": [
+                        {
+                            "id": RULE_UNVERIFIED,
+                            "shortDescription": {
+                                "text": "Dependency is not verified by trusted reviews"
+                            }
+                        },
+                        {
+                            "id": RULE_ISSUE,
+                            "shortDescription": {
+                                "text": "Dependency has open issues reported"
+                            }
+                        }
+                    ]
+                }
+            },
+            "results": results
+        }]
+    });
+
+    println!("{}", serde_json::to_string_pretty(&log)?);
+    Ok(())
+}
@@ -1,14 +1,15 @@
 use crate::{
     crates_io,
     deps::{
-        AccumulativeCrateDetails, CountWithTotal, CrateDetails, CrateInfo, CrateStats, OwnerSetSet,
+        AccumulativeCrateDetails, CountWithTotal, CrateDetails, CrateInfo, CrateStats,
+        CrateTimings, IgnorePattern, OwnerSetSet, TrustedAdvisory,
     },
-    opts::{CargoOpts, CrateVerify},
+    opts::{CargoOpts, CrateSelector, CrateVerify},
     prelude::*,
     repo::Repo,
     shared::{
-        cargo_min_ignore_list, get_geiger_count, is_digest_clean, read_known_owners_list,
-        PROJECT_SOURCE_CRATES_IO,
+        cargo_min_ignore_list, get_geiger_count, is_digest_clean, read_known_owners_with,
+        wipe_crate_source, PROJECT_IGNORE_FILE, PROJECT_SOURCE_CRATES_IO,
     },
 };
 use cargo::core::PackageId;
@@ -19,14 +20,119 @@ use crossbeam::{
     channel::{unbounded, Receiver},
 };
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     default::Default,
-    path::PathBuf,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
     sync::{atomic, Arc, Mutex},
+    time::Instant,
 };
 
+use crev_data::{proof::ContentCommon, Digest};
 use crev_lib::proofdb::*;
 
+/// Read proofs to use in this run only, skipping ones that fail verification
+fn load_extra_proofs(path: &Path) -> Result<Vec<crev_data::proof::Proof>> {
+    Ok(crev_data::proof::Proof::parse_from(path)
+        .with_context(|_| format!("Can't read proofs from {}", path.display()))?
+        .into_iter()
+        .filter(|proof| match proof.verify() {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Skipping invalid proof in {}: {}", path.display(), e);
+                false
+            }
+        })
+        .collect())
+}
+
+/// Read patterns of crates that don't require reviews, one per line
+fn load_ignore_file(path: &Path) -> Result<Vec<IgnorePattern>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    parse_ignore_list(&crev_common::read_file_to_string(path)?)
+        .map_err(|e| format_err!("Can't parse {}: {}", path.display(), e))
+}
+
+/// Parse an ignore list, skipping blank lines and `#` comments
+fn parse_ignore_list(content: &str) -> Result<Vec<IgnorePattern>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::parse)
+        .collect()
+}
+
+/// Patterns of the project `.crev/ignore` file in `workspace_dir`, and the `--ignore` ones
+fn load_ignore_patterns(
+    workspace_dir: &Path,
+    args_ignore: &[IgnorePattern],
+) -> Result<Vec<IgnorePattern>> {
+    let mut ignore_patterns = load_ignore_file(&workspace_dir.join(PROJECT_IGNORE_FILE))?;
+    ignore_patterns.extend(args_ignore.iter().cloned());
+    Ok(ignore_patterns)
+}
+
+/// Read crates to verify from stdin, one `name@version` per line
+///
+/// The crates don't have to be dependencies of the current project.
+fn load_stdin_crates(repo: &Repo) -> Result<HashMap<PackageId, CrateInfo>> {
+    let stdin = io::stdin();
+    let mut crate_info_by_id = HashMap::new();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let sel = CrateSelector::from_spec(line, true)?;
+        let pkg_id = repo
+            .find_pkgid_by_crate_selector(&sel)
+            .with_context(|_| format!("Can't find crate `{}`", line))?;
+        let pkg = repo.get_crate(&pkg_id)?;
+        crate_info_by_id.insert(pkg_id, CrateInfo::from_pkg(&pkg));
+    }
+    Ok(crate_info_by_id)
+}
+
+/// Crates reachable only through registry crates without trusted reviews
+///
+/// Local crates, and crates without reverse dependencies (the roots), are
+/// always descended into.
+fn find_pruned_crates(
+    graph: &crate::repo::Graph,
+    crate_info_by_id: &HashMap<PackageId, CrateInfo>,
+    has_trusted_reviews: impl Fn(PackageId) -> bool,
+) -> HashSet<PackageId> {
+    let mut pending: Vec<_> = graph
+        .get_all_pkg_ids()
+        .filter(|id| {
+            !crate_info_by_id.contains_key(id)
+                || graph.get_reverse_dependencies_of(*id).next().is_none()
+        })
+        .collect();
+    let mut reached: HashSet<_> = pending.iter().cloned().collect();
+
+    while let Some(id) = pending.pop() {
+        if crate_info_by_id.contains_key(&id) && !has_trusted_reviews(id) {
+            continue;
+        }
+        for dep_id in graph.get_dependencies_of(id) {
+            if reached.insert(dep_id) {
+                pending.push(dep_id);
+            }
+        }
+    }
+
+    crate_info_by_id
+        .keys()
+        .filter(|id| !reached.contains(id))
+        .cloned()
+        .collect()
+}
+
 /// Dependency scaner
 ///
 /// Offloads dependency scanning to concurrent worker threads.
@@ -42,8 +148,19 @@ pub struct Scanner {
     requirements: crev_lib::VerificationRequirements,
     skip_verified: bool,
     skip_known_owners: bool,
+    redownload: bool,
     recursive: bool,
+    recursive_depth: Option<u64>,
+    skip_geiger: bool,
+    jobs: usize,
+    // packages (name, version) already present at `--baseline`
+    baseline: Option<Arc<HashSet<(String, String)>>>,
+    // crates that don't require reviews, from `--ignore` and `.crev/ignore`
+    ignore_patterns: Vec<IgnorePattern>,
+    require_reviewers: Option<u64>,
     crate_info_by_id: HashMap<PackageId, CrateInfo>,
+    // packages below crates without trusted reviews, not scanned with `--prune-unreviewed`
+    pruned_crates_count: usize,
     // all the packages that we might need to potentially analyse
     all_crates_ids: Vec<PackageId>,
     // packages that we will have to return to the caller
@@ -56,7 +173,10 @@ pub struct Scanner {
 impl Scanner {
     pub fn new(args: &CrateVerify) -> Result<Scanner> {
         let local = crev_lib::Local::auto_create_or_open()?;
-        let db = local.load_db()?;
+        let mut db = local.load_db()?;
+        if let Some(ref trust_file) = args.trust_file {
+            db.import_from_iter(load_extra_proofs(trust_file)?.into_iter());
+        }
         let trust_set = if let Some(for_id) =
             local.get_for_id_from_str_opt(OptionDeref::as_deref(&args.common.for_id))?
         {
@@ -66,10 +186,15 @@ impl Scanner {
             crev_lib::proofdb::TrustSet::default()
         };
         let ignore_list = cargo_min_ignore_list();
-        let crates_io = crates_io::Client::new(&local)?;
-        let known_owners = read_known_owners_list().unwrap_or_else(|_| HashSet::new());
-        let requirements =
+        let mut crates_io = crates_io::Client::new(&local)?
+            .with_retries(args.api_retries.unwrap_or(crates_io::DEFAULT_RETRIES));
+        if let Some(ref url) = args.crates_io_api {
+            crates_io = crates_io.with_api_url(url)?;
+        }
+        let known_owners = read_known_owners_with(OptionDeref::as_deref(&args.owners_from))?;
+        let mut requirements =
             crev_lib::VerificationRequirements::from(args.common.requirements.clone());
+        requirements.since = args.since;
         let skip_verified = args.skip_verified;
         let skip_known_owners = args.skip_known_owners;
         let repo = Repo::auto_open_cwd(args.common.cargo_opts.clone())?;
@@ -79,6 +204,120 @@ impl Scanner {
             bail!("Unrealated crates are currently not supported");
         }
 
+        let baseline = if let Some(ref git_ref) = args.baseline {
+            Some(Arc::new(repo.get_lock_packages_at_git_ref(git_ref)?))
+        } else {
+            None
+        };
+
+        let ignore_patterns = load_ignore_patterns(repo.workspace_dir(), &args.ignore)?;
+
+        if args.stdin {
+            if args.common.crate_.name.is_some() {
+                bail!("Can't select a crate with `--stdin`");
+            }
+            if args.recursive || args.skip_indirect {
+                bail!("`--stdin` crates have no dependency graph; can't use `--recursive` or `--skip-indirect`");
+            }
+        }
+
+        if args.prune_unreviewed && args.recursive {
+            bail!("`--prune-unreviewed` skips crates that `--recursive` metrics need");
+        }
+
+        let (mut crate_info_by_id, mut selected_crates_ids, graph) = if args.stdin {
+            let crate_info_by_id = load_stdin_crates(&repo)?;
+            let selected_crates_ids = crate_info_by_id.keys().cloned().collect();
+            (
+                crate_info_by_id,
+                selected_crates_ids,
+                crate::repo::Graph::default(),
+            )
+        } else {
+            Self::load_project_crates(&repo, args)?
+        };
+
+        if args.only_changed {
+            // crates at versions not in the committed `Cargo.lock` are new or bumped
+            match repo.get_lock_packages_at_git_ref("HEAD") {
+                Ok(committed) => selected_crates_ids.retain(|id: &PackageId| {
+                    !committed.contains(&(id.name().to_string(), id.version().to_string()))
+                }),
+                Err(e) => eprintln!(
+                    "{}\nVerifying all the crates, not only the changed ones (`--only-changed`).",
+                    e
+                ),
+            }
+        }
+
+        // still scanned, as dependencies of other crates for `--recursive`
+        selected_crates_ids.retain(|id: &PackageId| {
+            !args
+                .exclude
+                .iter()
+                .any(|pattern| pattern.matches(&id.name(), id.version()))
+        });
+
+        let mut pruned_crates_count = 0;
+        if args.prune_unreviewed {
+            let pruned = find_pruned_crates(&graph, &crate_info_by_id, |id| {
+                db.get_package_reviews_for_package(
+                    PROJECT_SOURCE_CRATES_IO,
+                    Some(&id.name()),
+                    Some(id.version()),
+                )
+                .any(|pkg_review| {
+                    trust_set.get_effective_trust_level(&pkg_review.from.id)
+                        >= requirements.trust_level.into()
+                })
+            });
+            crate_info_by_id.retain(|id, _| !pruned.contains(id));
+            pruned_crates_count = selected_crates_ids
+                .iter()
+                .filter(|id| pruned.contains(id))
+                .count();
+            selected_crates_ids.retain(|id| !pruned.contains(id));
+        }
+
+        let all_crates_ids = crate_info_by_id.keys().cloned().collect();
+
+        Ok(Scanner {
+            db: Arc::new(db),
+            trust_set,
+            ignore_list,
+            crates_io: Arc::new(crates_io),
+            known_owners,
+            requirements,
+            skip_verified,
+            skip_known_owners,
+            redownload: args.redownload,
+            recursive: args.recursive || args.recursive_depth.is_some(),
+            recursive_depth: args.recursive_depth,
+            skip_geiger: args.skip_geiger,
+            jobs: args.jobs.unwrap_or_else(num_cpus::get).max(1),
+            baseline,
+            ignore_patterns,
+            require_reviewers: args.require_reviewers,
+            crate_info_by_id,
+            pruned_crates_count,
+            all_crates_ids,
+            selected_crates_ids,
+            cargo_opts: args.common.cargo_opts.clone(),
+            graph: Arc::new(graph),
+            crate_details_by_id: Default::default(),
+        })
+    }
+
+    /// Crates of the dependency graph of the current project, and those selected to verify
+    #[allow(clippy::type_complexity)]
+    fn load_project_crates(
+        repo: &Repo,
+        args: &CrateVerify,
+    ) -> Result<(
+        HashMap<PackageId, CrateInfo>,
+        HashSet<PackageId>,
+        crate::repo::Graph,
+    )> {
         let roots = repo.find_roots_by_crate_selector(&args.common.crate_)?;
         let roots_set: HashSet<_> = roots.iter().cloned().collect();
 
@@ -95,8 +334,6 @@ impl Scanner {
             .map(|pkg| (pkg.package_id(), CrateInfo::from_pkg(pkg)))
             .collect();
 
-        let all_crates_ids = crate_info_by_id.keys().cloned().collect();
-
         let selected_crates_ids = crate_info_by_id
             .iter()
             .filter_map(|(id, _crate_info)| {
@@ -114,29 +351,23 @@ impl Scanner {
             .cloned()
             .collect();
 
-        Ok(Scanner {
-            db: Arc::new(db),
-            trust_set,
-            ignore_list,
-            crates_io: Arc::new(crates_io),
-            known_owners,
-            requirements,
-            skip_verified,
-            skip_known_owners,
-            recursive: args.recursive,
-            crate_info_by_id,
-            all_crates_ids,
-            selected_crates_ids,
-            cargo_opts: args.common.cargo_opts.clone(),
-            graph: Arc::new(graph),
-            crate_details_by_id: Default::default(),
-        })
+        Ok((crate_info_by_id, selected_crates_ids, graph))
     }
 
     pub fn selected_crate_count(&self) -> usize {
         self.selected_crates_ids.len()
     }
 
+    /// Longest distance of each crate from the roots of the dependency graph, see `Graph::get_depths`
+    pub fn dependency_depths(&self) -> HashMap<PackageId, (u64, Option<PackageId>)> {
+        self.graph.get_depths()
+    }
+
+    /// Number of selected crates not scanned because of `--prune-unreviewed`
+    pub fn pruned_crate_count(&self) -> usize {
+        self.pruned_crates_count
+    }
+
     /// start computations on a new thread
     pub fn run(self) -> Receiver<CrateStats> {
         let (ready_tx, ready_rx) = unbounded();
@@ -162,7 +393,7 @@ impl Scanner {
         }
 
         let ready_tx_count = Arc::new(atomic::AtomicUsize::new(0));
-        for _ in 0..num_cpus::get() {
+        for _ in 0..self.jobs {
             let pending_rx = pending_rx.clone();
             let pending_tx = pending_tx.clone();
             let ready_tx = ready_tx.clone();
@@ -194,7 +425,8 @@ impl Scanner {
 
                             let info = self_clone.crate_info_by_id[&pkg_id].to_owned();
 
-                            let details = self_clone.get_crate_details(&info);
+                            let mut timings = CrateTimings::default();
+                            let details = self_clone.get_crate_details(&info, &mut timings);
                             {
                                 let mut crate_details_by_id =
                                     self_clone.crate_details_by_id.lock().unwrap();
@@ -214,7 +446,15 @@ impl Scanner {
                                     details
                                 };
 
-                                let stats = CrateStats { info, details };
+                                let in_baseline = self_clone.is_in_baseline(&info);
+                                let ignored = self_clone.is_ignored(&info);
+                                let stats = CrateStats {
+                                    info,
+                                    details,
+                                    timings,
+                                    in_baseline,
+                                    ignored,
+                                };
 
                                 ready_tx
                                     .send(stats)
@@ -241,33 +481,282 @@ impl Scanner {
         ready_rx
     }
 
-    fn get_crate_details(&mut self, info: &CrateInfo) -> Result<Option<CrateDetails>> {
+    fn is_in_baseline(&self, info: &CrateInfo) -> bool {
+        match self.baseline {
+            Some(ref baseline) => {
+                baseline.contains(&(info.id.name().to_string(), info.id.version().to_string()))
+            }
+            None => false,
+        }
+    }
+
+    fn is_ignored(&self, info: &CrateInfo) -> bool {
+        let name = info.id.name();
+        self.ignore_patterns
+            .iter()
+            .any(|pattern| pattern.matches(&name, info.id.version()))
+    }
+
+    /// Reviews of a crate version from reviewers with enough trust
+    fn get_trusted_version_reviews(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> Vec<crev_data::proof::review::Package> {
+        self.db
+            .get_package_reviews_for_package(PROJECT_SOURCE_CRATES_IO, Some(name), Some(version))
+            .filter(|pkg_review| {
+                self.trust_set
+                    .get_effective_trust_level(&pkg_review.from.id)
+                    >= self.requirements.trust_level.into()
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Advisories affecting a crate version, from reviewers with enough trust
+    fn get_trusted_advisories(&self, name: &str, version: &Version) -> Vec<TrustedAdvisory> {
+        self.db
+            .get_advisories_for_version(PROJECT_SOURCE_CRATES_IO, name, version)
+            .filter(|pkg_review| {
+                self.trust_set
+                    .get_effective_trust_level(&pkg_review.from.id)
+                    >= self.requirements.trust_level.into()
+            })
+            .flat_map(|pkg_review| {
+                pkg_review
+                    .advisories
+                    .iter()
+                    .filter(move |advisory| {
+                        advisory.is_for_version_when_reported_in_version(
+                            version,
+                            &pkg_review.package.version,
+                        )
+                    })
+                    .map(move |advisory| TrustedAdvisory {
+                        author: pkg_review.from.to_owned(),
+                        severity: advisory.severity,
+                        ids: advisory.ids.clone(),
+                        comment: advisory.comment.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Print why the crate `name` is or isn't verified
+    pub fn explain(&self, name: &str) -> Result<()> {
+        let mut infos: Vec<_> = self
+            .crate_info_by_id
+            .values()
+            .filter(|info| info.id.name().as_str() == name)
+            .collect();
+        if infos.is_empty() {
+            bail!("Crate {} is not a dependency of the project", name);
+        }
+        infos.sort_by(|a, b| a.id.version().cmp(b.id.version()));
+
+        for info in infos {
+            self.explain_crate(info)?;
+        }
+        Ok(())
+    }
+
+    /// Digest of the crate source, of the first supported digest type
+    /// that reviews of this version match
+    ///
+    /// Returns the default digest type if none match, and whether
+    /// the digest is unclean.
+    fn get_matching_digest(&self, info: &CrateInfo) -> Result<(Digest, String, bool)> {
         let pkg_name = info.id.name();
         let pkg_version = info.id.version();
-        info.download_if_needed(self.cargo_opts.clone())?;
-        let geiger_count = get_geiger_count(&info.root).ok();
+        let default_type = crev_data::proof::default_digest_type();
         let digest = crev_lib::get_dir_digest(&info.root, &self.ignore_list)?;
-        let unclean_digest = !is_digest_clean(&self.db, &pkg_name, &pkg_version, &digest);
-        let result = self
+        if is_digest_clean(&self.db, &pkg_name, pkg_version, &digest) {
+            return Ok((digest, default_type, false));
+        }
+
+        // other digest types are calculated only if some reviews use them
+        let other_types: BTreeSet<_> = self
             .db
-            .verify_package_digest(&digest, &self.trust_set, &self.requirements);
-        let verified = result.is_verified();
-        if verified && self.skip_verified && !self.recursive {
-            return Ok(None);
+            .get_package_reviews_for_package(
+                PROJECT_SOURCE_CRATES_IO,
+                Some(&pkg_name),
+                Some(pkg_version),
+            )
+            .map(|review| review.package.digest_type.clone())
+            .filter(|digest_type| *digest_type != default_type)
+            .collect();
+        for digest_type in other_types {
+            if let Some(other_digest) =
+                crev_lib::get_dir_digest_of_type(&info.root, &self.ignore_list, &digest_type)?
+            {
+                if is_digest_clean(&self.db, &pkg_name, pkg_version, &other_digest) {
+                    return Ok((other_digest, digest_type, false));
+                }
+            }
         }
 
+        Ok((digest, default_type, true))
+    }
+
+    fn explain_crate(&self, info: &CrateInfo) -> Result<()> {
         let pkg_name = info.id.name().to_string();
+        let pkg_version = info.id.version();
+        let requirements = &self.requirements;
+        info.download_if_needed(self.cargo_opts.clone())?;
+        let (digest, digest_type, _) = self.get_matching_digest(info)?;
+
+        println!("{} {}", pkg_name, pkg_version);
+        println!("  digest of the local source: {} ({})", digest, digest_type);
+        println!(
+            "  requirements: {} trusted review(s) with trust >= {}, thoroughness >= {}, understanding >= {}",
+            requirements.redundancy,
+            requirements.trust_level,
+            requirements.thoroughness,
+            requirements.understanding
+        );
+        if let Some(ref since) = requirements.since {
+            println!("  only reviews since {} count", since);
+        }
 
-        let version_reviews: Vec<_> = self
+        let verdicts = self
+            .db
+            .get_package_review_verdicts(&digest, &self.trust_set, requirements);
+        let other_digest_count = self
             .db
             .get_package_reviews_for_package(
                 PROJECT_SOURCE_CRATES_IO,
                 Some(&pkg_name),
-                Some(&info.id.version()),
+                Some(pkg_version),
             )
-            .collect();
+            .filter(|review| review.package.digest != digest.as_slice())
+            .count();
+        if verdicts.is_empty() {
+            println!("  no reviews of this digest");
+        }
+
+        let mut trust_count = 0;
+        let mut negative_count = 0;
+        for (review, verdict) in &verdicts {
+            match verdict {
+                ReviewVerdict::Counts => trust_count += 1,
+                ReviewVerdict::Negative => negative_count += 1,
+                _ => {}
+            }
+            let r = &review.review;
+            println!(
+                "  review by {} {}: rating {:?}, thoroughness {}, understanding {}, trust {}; {}",
+                review.from.id,
+                review.from.url.url,
+                r.rating,
+                r.thoroughness,
+                r.understanding,
+                self.trust_set
+                    .get_effective_trust_level_opt(&review.from.id)
+                    .map(|level| level.to_string())
+                    .unwrap_or_else(|| "none".into()),
+                verdict.description()
+            );
+        }
+        if other_digest_count > 0 {
+            println!(
+                "  {} review(s) of this version don't match the digest of the local source",
+                other_digest_count
+            );
+        }
+
+        let mut missing_reviewers = false;
+        if let Some(require_reviewers) = self.require_reviewers {
+            let independent_reviewers =
+                self.db
+                    .count_independent_reviewers(&digest, &self.trust_set, requirements);
+            println!(
+                "  {} of {} required independent reviewer(s) of this version",
+                independent_reviewers, require_reviewers
+            );
+            missing_reviewers = independent_reviewers < require_reviewers;
+        }
+
+        let status = if negative_count > 0 {
+            format!(
+                "not verified: {} negative review(s) from trusted reviewers",
+                negative_count
+            )
+        } else if trust_count >= requirements.redundancy && missing_reviewers {
+            "not verified: not enough independent reviewers".to_owned()
+        } else if trust_count >= requirements.redundancy {
+            format!(
+                "verified: {} of {} required trusted review(s)",
+                trust_count, requirements.redundancy
+            )
+        } else {
+            format!(
+                "not verified: {} of {} required trusted review(s)",
+                trust_count, requirements.redundancy
+            )
+        };
+        println!("  {}", status);
+        Ok(())
+    }
+
+    fn get_crate_details(
+        &mut self,
+        info: &CrateInfo,
+        timings: &mut CrateTimings,
+    ) -> Result<Option<CrateDetails>> {
+        let pkg_version = info.id.version();
+        let start = Instant::now();
+        info.download_if_needed(self.cargo_opts.clone())?;
+        timings.download = start.elapsed();
+        let start = Instant::now();
+        let (mut digest, mut digest_type, mut unclean_digest) = self.get_matching_digest(info)?;
+        timings.digest = start.elapsed();
+        let redownloaded = self.redownload && unclean_digest;
+        if redownloaded {
+            let start = Instant::now();
+            wipe_crate_source(&info.root)?;
+            info.download_if_needed(self.cargo_opts.clone())?;
+            timings.download += start.elapsed();
+            let start = Instant::now();
+            let matching = self.get_matching_digest(info)?;
+            digest = matching.0;
+            digest_type = matching.1;
+            unclean_digest = matching.2;
+            timings.digest += start.elapsed();
+        }
+        let start = Instant::now();
+        let geiger_count = if self.skip_geiger {
+            None
+        } else {
+            get_geiger_count(&info.root).ok()
+        };
+        timings.geiger = start.elapsed();
+        let mut result =
+            self.db
+                .verify_package_digest(&digest, &self.trust_set, &self.requirements);
+        let pkg_name = info.id.name().to_string();
 
-        let version_reviews_count = version_reviews.len();
+        let trusted_version_reviews = self.get_trusted_version_reviews(&pkg_name, pkg_version);
+        let independent_reviewers =
+            self.db
+                .count_independent_reviewers(&digest, &self.trust_set, &self.requirements);
+        if let Some(require_reviewers) = self.require_reviewers {
+            if result == crev_lib::VerificationStatus::Verified
+                && independent_reviewers < require_reviewers
+            {
+                result = crev_lib::VerificationStatus::Insufficient;
+            }
+        }
+        let verified = result.is_verified();
+        if verified && self.skip_verified && !self.recursive {
+            return Ok(None);
+        }
+
+        let version_reviews_count = self.db.get_package_review_count(
+            PROJECT_SOURCE_CRATES_IO,
+            Some(&pkg_name),
+            Some(&info.id.version()),
+        );
         let total_reviews_count =
             self.db
                 .get_package_review_count(PROJECT_SOURCE_CRATES_IO, Some(&pkg_name), None);
@@ -327,8 +816,12 @@ impl Scanner {
             count: issues_from_trusted.len() as u64,
             total: issues_from_all.len() as u64,
         };
+        let mut trusted_issue_ids: Vec<_> = issues_from_trusted.keys().cloned().collect();
+        trusted_issue_ids.sort();
 
+        let start = Instant::now();
         let loc = crate::tokei::get_rust_line_count(&info.root).ok();
+        timings.loc = start.elapsed();
 
         let latest_trusted_version = self.db.find_latest_trusted_version(
             &self.trust_set,
@@ -337,7 +830,31 @@ impl Scanner {
             &self.requirements,
         );
 
-        let owner_set = OwnerSetSet::new(info.id, owner_list.unwrap_or_else(|| vec![]));
+        if verified {
+            if let Some(ref owner_list) = owner_list {
+                // best effort; the cache is only used to detect changes later on
+                let _ = self
+                    .crates_io
+                    .record_reviewed_owners(&pkg_name, pkg_version, owner_list);
+            }
+        }
+
+        // owners changed since the latest trusted version was reviewed
+        let owners_changed = match (&latest_trusted_version, &owner_list) {
+            (Some(reviewed_version), Some(owner_list)) => self
+                .crates_io
+                .get_reviewed_owners(&pkg_name, reviewed_version)
+                .ok()
+                .and_then(|o| o)
+                .map(|reviewed_owners| {
+                    let reviewed_owners: HashSet<_> = reviewed_owners.into_iter().collect();
+                    let owners: HashSet<_> = owner_list.iter().cloned().collect();
+                    reviewed_owners != owners
+                }),
+            _ => None,
+        };
+
+        let owner_set = OwnerSetSet::new(info.id, owner_list.clone().unwrap_or_else(|| vec![]));
 
         let accumulative_own = AccumulativeCrateDetails {
             trust: result,
@@ -356,7 +873,7 @@ impl Scanner {
 
             for dep_pkg_id in self
                 .graph
-                .get_recursive_dependencies_of(info.id)
+                .get_recursive_dependencies_of(info.id, self.recursive_depth)
                 .into_iter()
             {
                 match crate_details_by_id
@@ -371,23 +888,98 @@ impl Scanner {
             }
         }
 
+        let trusted_advisories = self.get_trusted_advisories(&pkg_name, pkg_version);
+
+        let latest_trusted_review_date = trusted_version_reviews
+            .iter()
+            .map(|pkg_review| pkg_review.date().to_owned())
+            .max();
+
         Ok(Some(CrateDetails {
             digest,
-            trusted_reviewers: version_reviews
+            digest_type,
+            trusted_reviewers: trusted_version_reviews
                 .into_iter()
                 .map(|pkg_review| pkg_review.from.to_owned())
-                .filter(|id| {
-                    self.trust_set.get_effective_trust_level(&id.id)
-                        >= self.requirements.trust_level.into()
-                })
                 .collect(),
+            trusted_advisories,
+            trusted_issue_ids,
+            independent_reviewers,
+            latest_trusted_review_date,
             latest_trusted_version,
             version_reviews: version_review_count,
             version_downloads,
             known_owners,
+            owner_list,
+            owners_changed,
             unclean_digest,
+            redownloaded,
             accumulative_own,
             accumulative,
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_ignored(patterns: &[IgnorePattern], name: &str, version: &str) -> bool {
+        let version = Version::parse(version).unwrap();
+        patterns
+            .iter()
+            .any(|pattern| pattern.matches(name, &version))
+    }
+
+    #[test]
+    fn ignore_list_skips_comments_and_blank_lines() -> Result<()> {
+        let patterns = parse_ignore_list(
+            "# vendored by the project\n\
+             \n\
+             winapi*\n\
+             \x20\x20# indented comment\n\
+             rand = \"0.6\"\n\
+             \x20\x20libc  \n",
+        )?;
+        assert_eq!(patterns.len(), 3);
+        assert!(is_ignored(&patterns, "winapi-util", "0.1.2"));
+        assert!(is_ignored(&patterns, "rand", "0.6.5"));
+        assert!(!is_ignored(&patterns, "rand", "0.7.0"));
+        assert!(is_ignored(&patterns, "libc", "0.2.60"));
+        assert!(!is_ignored(&patterns, "serde", "1.0.0"));
+
+        assert!(parse_ignore_list("rand = not-a-version\n").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ignore_file_is_merged_with_args() -> Result<()> {
+        let workspace_dir =
+            std::env::temp_dir().join(format!("crev-ignore-test-{}", std::process::id()));
+        let args_ignore = vec!["serde".parse()?];
+
+        // no `.crev/ignore`: only `--ignore`
+        let patterns = load_ignore_patterns(&workspace_dir, &args_ignore)?;
+        assert_eq!(patterns.len(), 1);
+        assert!(is_ignored(&patterns, "serde", "1.0.0"));
+
+        crev_common::store_str_to_file(
+            &workspace_dir.join(PROJECT_IGNORE_FILE),
+            "# comment\nrand = \"0.6\"\n",
+        )?;
+        let patterns = load_ignore_patterns(&workspace_dir, &args_ignore);
+        let bad_file =
+            crev_common::store_str_to_file(&workspace_dir.join(PROJECT_IGNORE_FILE), "= 0.6\n")
+                .map_err(failure::Error::from)
+                .and_then(|()| load_ignore_patterns(&workspace_dir, &args_ignore));
+        std::fs::remove_dir_all(&workspace_dir)?;
+
+        let patterns = patterns?;
+        assert_eq!(patterns.len(), 2);
+        assert!(is_ignored(&patterns, "rand", "0.6.1"));
+        assert!(is_ignored(&patterns, "serde", "1.0.0"));
+        assert!(!is_ignored(&patterns, "rand", "0.7.0"));
+        assert!(bad_file.is_err());
+        Ok(())
+    }
+}
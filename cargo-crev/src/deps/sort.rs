@@ -0,0 +1,98 @@
+// Ordering `crate verify` rows by `--sort`, and grouping them by `--group-by-trust`
+use crate::{deps::CrateStats, opts::VerifySort};
+use crev_lib::VerificationStatus;
+use std::cmp::Ordering;
+
+/// Compare known values, flipped if `reverse`; unknown ones always go last
+fn cmp_known_first<T: Ord>(a: Option<T>, b: Option<T>, reverse: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if reverse => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+pub fn sort_deps(deps: &mut [CrateStats], key: VerifySort, reverse: bool) {
+    deps.sort_by(|a, b| match key {
+        VerifySort::Name => cmp_known_first(
+            Some((a.info.id.name(), a.info.id.version())),
+            Some((b.info.id.name(), b.info.id.version())),
+            reverse,
+        ),
+        VerifySort::Geiger => cmp_known_first(
+            a.details().and_then(|d| d.accumulative.geiger_count),
+            b.details().and_then(|d| d.accumulative.geiger_count),
+            reverse,
+        ),
+        VerifySort::Loc => cmp_known_first(
+            a.details().and_then(|d| d.accumulative.loc),
+            b.details().and_then(|d| d.accumulative.loc),
+            reverse,
+        ),
+        VerifySort::Reviews => cmp_known_first(
+            a.details().map(|d| d.version_reviews.count),
+            b.details().map(|d| d.version_reviews.count),
+            reverse,
+        ),
+        VerifySort::Trust => cmp_known_first(
+            a.details().map(|d| d.accumulative.trust),
+            b.details().map(|d| d.accumulative.trust),
+            reverse,
+        ),
+        VerifySort::Downloads => cmp_known_first(
+            a.details()
+                .and_then(|d| d.version_downloads.as_ref())
+                .map(|d| d.count),
+            b.details()
+                .and_then(|d| d.version_downloads.as_ref())
+                .map(|d| d.count),
+            reverse,
+        ),
+    });
+}
+
+/// Section of `--group-by-trust` output, in the order they are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TrustGroup {
+    Verified,
+    /// Reviewed, but not by enough trusted reviewers
+    LowTrust,
+    None,
+    Distrusted,
+    Failed,
+}
+
+impl TrustGroup {
+    pub fn title(self) -> &'static str {
+        match self {
+            TrustGroup::Verified => "verified",
+            TrustGroup::LowTrust => "low-trust",
+            TrustGroup::None => "none",
+            TrustGroup::Distrusted => "distrusted",
+            TrustGroup::Failed => "failed",
+        }
+    }
+}
+
+/// Group of a crate; `None` for the ones that aren't displayed, eg. with `--skip-verified`
+pub fn trust_group(stats: &CrateStats) -> Option<TrustGroup> {
+    match stats.details {
+        Err(_) => Some(TrustGroup::Failed),
+        Ok(None) => None,
+        Ok(Some(ref details)) => Some(match details.accumulative.trust {
+            VerificationStatus::Verified => TrustGroup::Verified,
+            VerificationStatus::Insufficient if details.version_reviews.total > 0 => {
+                TrustGroup::LowTrust
+            }
+            VerificationStatus::Insufficient => TrustGroup::None,
+            VerificationStatus::Negative => TrustGroup::Distrusted,
+        }),
+    }
+}
+
+/// Put the crates of each group together, keeping their order otherwise
+pub fn group_deps_by_trust(deps: &mut [CrateStats]) {
+    deps.sort_by_key(trust_group);
+}
@@ -0,0 +1,69 @@
+// Re-running `crate verify` whenever the dependencies change
+use crate::{
+    deps::verify_deps, opts::CrateVerify, prelude::*, repo::Repo, shared::CommandExitStatus,
+};
+use crossterm::{ClearType, Terminal, TerminalCursor};
+use notify::{DebouncedEvent, RecursiveMode, Watcher};
+use std::{path::Path, sync::mpsc, time::Duration};
+
+const WATCHED_FILES: &[&str] = &["Cargo.toml", "Cargo.lock"];
+
+fn is_watched_file(path: &Path) -> bool {
+    match path.file_name() {
+        Some(name) => WATCHED_FILES.iter().any(|watched| name == *watched),
+        None => false,
+    }
+}
+
+fn is_relevant_event(event: &DebouncedEvent) -> bool {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Remove(path)
+        | DebouncedEvent::Rename(_, path) => is_watched_file(path),
+        _ => false,
+    }
+}
+
+fn clear_screen() -> Result<()> {
+    Terminal::new().clear(ClearType::All)?;
+    TerminalCursor::new().goto(0, 0)?;
+    Ok(())
+}
+
+/// Verify dependencies, then again each time `Cargo.toml` or `Cargo.lock` changes
+///
+/// Runs until interrupted with Ctrl+C. Without a terminal on stdout,
+/// verifies only once.
+pub fn verify_deps_watch(args: CrateVerify) -> Result<CommandExitStatus> {
+    if !atty::is(atty::Stream::Stdout) {
+        eprintln!("stdout is not a terminal; not watching for changes.");
+        return verify_deps(args);
+    }
+
+    // nothing to clean up, but don't report the interruption as a failure
+    ctrlc::set_handler(|| std::process::exit(0))?;
+
+    let workspace_dir = Repo::auto_open_cwd(args.common.cargo_opts.clone())?
+        .workspace_dir()
+        .to_owned();
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::watcher(tx, Duration::from_millis(500))?;
+    // watching the directory catches editors replacing files, too
+    watcher.watch(&workspace_dir, RecursiveMode::NonRecursive)?;
+
+    loop {
+        clear_screen()?;
+        if let Err(e) = verify_deps(args.clone()) {
+            eprintln!("Error: {}", e);
+        }
+        eprintln!("Watching {} for changes...", WATCHED_FILES.join(" and "));
+
+        loop {
+            let event = rx.recv()?;
+            if is_relevant_event(&event) {
+                break;
+            }
+        }
+    }
+}
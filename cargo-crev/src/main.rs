@@ -9,7 +9,7 @@ use self::prelude::*;
 
 use crev_common::convert::OptionDeref;
 use crev_lib::{self, local::Local};
-use std::io::BufRead;
+use std::io::{BufRead, Write};
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -17,6 +17,7 @@ use structopt::StructOpt;
 /// Documentation
 pub mod doc;
 
+mod advisory_db;
 mod crates_io;
 mod deps;
 mod opts;
@@ -29,7 +30,7 @@ mod tokei;
 mod tui;
 
 use crate::{repo::*, review::*, shared::*};
-use crev_data::Id;
+use crev_data::{proof::trust::TrustLevel, Id};
 use crev_lib::proofdb::{ProofDB, TrustSet};
 use crev_lib::TrustProofType::{self, *};
 
@@ -49,12 +50,180 @@ fn print_ids<'a>(
     Ok(())
 }
 
-fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
+/// Print how `id` gets its trust level in the WoT of `for_id`
+///
+/// The path marked with `*` determines the effective trust level.
+fn explain_trust(
+    db: &ProofDB,
+    for_id: &Id,
+    id: &Id,
+    params: &crev_lib::TrustDistanceParams,
+    max_paths: usize,
+) {
+    let trust_set = db.calculate_trust_set(for_id, params);
+    let distrusters: Vec<_> = trust_set.distrusted_by(id).collect();
+    if !distrusters.is_empty() {
+        println!("{} is distrusted by:", id);
+        for distruster in distrusters {
+            println!("  {}", distruster);
+        }
+        return;
+    }
+    if !trust_set.contains_trusted(id) {
+        println!(
+            "{} is not trusted: no path of trust proofs within the distance",
+            id
+        );
+        return;
+    }
+    if id == for_id {
+        println!("{} is the root of the WoT", id);
+        return;
+    }
+
+    println!(
+        "{} effective trust: {}",
+        id,
+        trust_set.get_effective_trust_level(id)
+    );
+    let paths = db.find_trust_paths(&trust_set, for_id, id, params, max_paths);
+    for (i, path) in paths.iter().enumerate() {
+        let steps: Vec<_> = path
+            .steps
+            .iter()
+            .map(|(step_id, level)| format!("{} ({})", step_id, level))
+            .collect();
+        println!(
+            "{} {:6} distance {:3}: {} -> {}",
+            if i == 0 { "*" } else { " " },
+            path.effective_trust(),
+            path.distance,
+            for_id,
+            steps.join(" -> ")
+        );
+    }
+    if paths.len() == max_paths {
+        eprintln!(
+            "Showing only the first {} paths; see `--max-paths`",
+            max_paths
+        );
+    }
+}
+
+/// Make generated completion scripts complete crate names from the dependencies
+///
+/// Static completions know nothing about the current project, so the
+/// crate name positionals call the hidden `complete-crates` command instead.
+/// Only bash and zsh are supported; other scripts are returned as they are.
+fn add_crate_name_completions(shell: structopt::clap::Shell, script: String) -> String {
+    use structopt::clap::Shell;
+    match shell {
+        // `opts` is expanded on every completion, so the list is always current
+        Shell::Bash => script.replace(" <name> ", " $(cargo crev complete-crates 2>/dev/null) "),
+        Shell::Zsh => {
+            let script = script
+                .replace("::name:_files'", "::name:_cargo_crev_crates'")
+                .replace(
+                    ":name -- Crate name:_files'",
+                    ":name -- Crate name:_cargo_crev_crates'",
+                );
+            let helper = r#"_cargo_crev_crates() {
+    local -a crates
+    crates=(${(f)"$(cargo crev complete-crates 2>/dev/null)"})
+    compadd -a crates
+}
+
+"#;
+            match script.rfind("_cargo \"$@\"") {
+                Some(pos) => format!("{}{}{}", &script[..pos], helper, &script[pos..]),
+                None => script,
+            }
+        }
+        _ => script,
+    }
+}
+
+/// Print all own Ids stored locally, marking the current one
+///
+/// Ids are only checked for an empty passphrase, so no passphrase is asked for.
+fn print_own_ids_status(local: &Local) -> Result<()> {
+    let current_id = local.get_current_userid_opt()?;
+    for locked_id in local.list_locked_ids()? {
+        let pub_id = locked_id.to_pubid();
+        println!(
+            "{} {:13} {:7} {}",
+            pub_id.id,
+            if locked_id.to_unlocked("").is_ok() {
+                "no-passphrase"
+            } else {
+                "locked"
+            },
+            if current_id.as_ref() == Some(&pub_id.id) {
+                "current"
+            } else {
+                ""
+            },
+            pub_id.url.url
+        );
+    }
+    Ok(())
+}
+
+/// Print the whole trust graph in Graphviz DOT format
+fn print_trust_graph_dot(db: &ProofDB) -> Result<()> {
+    println!("digraph trust {{");
+    for id in db.all_known_ids() {
+        let label = db
+            .lookup_url(&id)
+            .map(|url| url.url.clone())
+            .unwrap_or_else(|| id.to_string());
+        println!("  \"{}\" [label=\"{}\"];", id, label);
+    }
+    let mut edges: Vec<_> = db.all_trust_edges().collect();
+    edges.sort();
+    for (from, to, level) in edges {
+        let style = match level {
+            TrustLevel::Distrust => "color=red, style=dashed",
+            TrustLevel::None => "color=gray, style=dotted",
+            TrustLevel::Low => "color=orange",
+            TrustLevel::Medium => "color=blue",
+            TrustLevel::High => "color=darkgreen, penwidth=2",
+        };
+        println!(
+            "  \"{}\" -> \"{}\" [label=\"{}\", {}];",
+            from, to, level, style
+        );
+    }
+    println!("}}");
+    Ok(())
+}
+
+fn run_command(
+    command: opts::Command,
+    proof_dir: Option<PathBuf>,
+    rebuild_index: bool,
+) -> Result<CommandExitStatus> {
+    if let Some(proof_dir) = proof_dir {
+        // `Local` is opened all over the place; the env var lets every
+        // one of them (and `cargo crev` run from a `goto` shell) pick up the override.
+        // Absolute, as the current directory changes (`goto`, running `git`).
+        let proof_dir = std::env::current_dir()?.join(proof_dir);
+        std::env::set_var(crev_lib::local::ROOT_DIR_ENV, proof_dir);
+    }
+    if rebuild_index {
+        Local::auto_create_or_open()?.remove_proof_index()?;
+    }
+
     match command {
         opts::Command::Id(args) => match args {
             opts::Id::New(args) => {
                 let local = Local::auto_create_or_open()?;
-                let res = local.generate_id(args.url, args.github_username, args.use_https_push);
+                let res = local.generate_id(
+                    args.url,
+                    args.github_username,
+                    args.use_https_push,
+                    OptionDeref::as_deref(&args.from_seed),
+                );
                 if res.is_err() {
                     eprintln!("Visit https://github.com/dpc/crev/wiki/Proof-Repository for help.");
                 }
@@ -70,9 +239,31 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 let local = Local::auto_open()?;
                 local.show_own_ids()?;
             }
+            opts::Id::Whoami => {
+                let local = Local::auto_open()?;
+                match local.read_current_locked_id_opt()? {
+                    Some(id) => {
+                        let id = id.to_pubid();
+                        println!("{} {}", id.id, id.url.url);
+                    }
+                    None => bail!("No current Id; create one with `cargo crev id new`"),
+                }
+            }
             opts::Id::Export(args) => {
                 let local = Local::auto_open()?;
-                println!("{}", local.export_locked_id(args.id)?);
+                let exported = if args.public_only {
+                    let pub_id = local.export_pub_id(args.id)?;
+                    // only the Id on stdout, to be passed to `id trust` as is
+                    eprintln!("Proof repository: {}", pub_id.url.url);
+                    pub_id.id.to_string()
+                } else {
+                    local.export_locked_id(args.id, args.armor)?
+                };
+                if args.qr {
+                    print!("{}", term::qr_code_string(exported.as_bytes())?);
+                } else {
+                    println!("{}", exported);
+                }
             }
             opts::Id::Import => {
                 let local = Local::auto_create_or_open()?;
@@ -87,14 +278,68 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                     local.clone_proof_dir_from_git(&id.url.url, false)?;
                 }
             }
+            opts::Id::Passwd => {
+                let local = Local::auto_open()?;
+                local.change_current_id_passphrase(
+                    &crev_common::read_passphrase,
+                    &crev_common::read_new_passphrase,
+                )?;
+            }
+            opts::Id::Migrate(args) => {
+                migrate_id_proofs(&args)?;
+            }
+            opts::Id::RenameOwner(args) => {
+                rename_known_owner(&args.from, &args.to)?;
+            }
+            opts::Id::Fingerprint(args) => {
+                let id = match args.id {
+                    Some(id) => Id::crevid_from_str(&id)?,
+                    None => Local::auto_open()?.get_current_userid()?,
+                };
+                println!("{}", id.fingerprint());
+            }
+            opts::Id::ListRepos(args) => print_fetched_repos(args.output_format)?,
             opts::Id::Trust(args) => {
-                create_trust_proof(args.pub_ids, Trust, &args.common_proof_create)?;
+                let dry_run_params = args.dry_run_params();
+                create_trust_proof(
+                    args.pub_ids,
+                    Trust,
+                    args.level,
+                    args.comment,
+                    dry_run_params,
+                    &args.common_proof_create,
+                )?;
             }
             opts::Id::Untrust(args) => {
-                create_trust_proof(args.pub_ids, Untrust, &args.common_proof_create)?;
+                if args.level.is_some() {
+                    bail!("`--level` can't be used with `untrust`");
+                }
+                let dry_run_params = args.dry_run_params();
+                create_trust_proof(
+                    args.pub_ids,
+                    Untrust,
+                    None,
+                    args.comment,
+                    dry_run_params,
+                    &args.common_proof_create,
+                )?;
             }
             opts::Id::Distrust(args) => {
-                create_trust_proof(args.pub_ids, Distrust, &args.common_proof_create)?;
+                if args.level.is_some() {
+                    bail!("`--level` can't be used with `distrust`");
+                }
+                let dry_run_params = args.dry_run_params();
+                create_trust_proof(
+                    args.pub_ids,
+                    Distrust,
+                    None,
+                    args.comment,
+                    dry_run_params,
+                    &args.common_proof_create,
+                )?;
+            }
+            opts::Id::TrustRemove(args) => {
+                remove_trust_proofs(&args.pub_id)?;
             }
             opts::Id::Query(cmd) => match cmd {
                 opts::IdQuery::Current { trust_params } => {
@@ -107,9 +352,14 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                         print_ids(Some(id.id).as_ref().into_iter(), &trust_set, &db)?;
                     }
                 }
-                opts::IdQuery::Own { trust_params } => {
+                opts::IdQuery::Own {
+                    trust_params,
+                    include_locked,
+                } => {
                     let local = Local::auto_open()?;
-                    if let Some(id) = local.read_current_locked_id_opt()? {
+                    if include_locked {
+                        print_own_ids_status(&local)?;
+                    } else if let Some(id) = local.read_current_locked_id_opt()? {
                         let id = id.to_pubid();
                         let db = local.load_db()?;
                         let trust_set = db.calculate_trust_set(&id.id, &trust_params.into());
@@ -125,32 +375,74 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                     trust_params,
                     for_id,
                     trust_level,
+                    warn_cycles,
+                    transitive_sources,
                 } => {
                     let local = crev_lib::Local::auto_open()?;
                     let db = local.load_db()?;
                     let for_id = local.get_for_id_from_str(OptionDeref::as_deref(&for_id))?;
                     let trust_set = db.calculate_trust_set(&for_id, &trust_params.into());
 
-                    print_ids(
-                        trust_set.trusted_ids().filter(|id| {
-                            trust_set.get_effective_trust_level(id)
-                                >= trust_level.trust_level.into()
-                        }),
-                        &trust_set,
-                        &db,
-                    )?;
+                    let ids = trust_set.trusted_ids().filter(|id| {
+                        trust_set.get_effective_trust_level(id) >= trust_level.level().into()
+                    });
+                    if transitive_sources {
+                        for id in ids {
+                            print_ids(std::iter::once(id), &trust_set, &db)?;
+                            if *id == for_id {
+                                continue;
+                            }
+                            let mut sources: Vec<_> = trust_set.trusted_by(id).collect();
+                            sources.sort();
+                            for (source, level) in sources {
+                                println!("    via {} {:6}", source, level);
+                            }
+                        }
+                    } else {
+                        print_ids(ids, &trust_set, &db)?;
+                    }
+
+                    if warn_cycles {
+                        for cycle in db.find_trust_cycles(&trust_set) {
+                            let ids: Vec<_> = cycle
+                                .iter()
+                                .chain(cycle.first())
+                                .map(ToString::to_string)
+                                .collect();
+                            eprintln!("Trust cycle: {}", ids.join(" -> "));
+                        }
+                    }
+                }
+                opts::IdQuery::ExplainTrust {
+                    id,
+                    trust_params,
+                    for_id,
+                    max_paths,
+                } => {
+                    let local = crev_lib::Local::auto_open()?;
+                    let db = local.load_db()?;
+                    let for_id = local.get_for_id_from_str(OptionDeref::as_deref(&for_id))?;
+                    let id = Id::crevid_from_str(&id)?;
+                    explain_trust(&db, &for_id, &id, &trust_params.into(), max_paths);
                 }
                 // TODO: move to crev-lib
                 opts::IdQuery::All {
                     trust_params,
                     for_id,
+                    output_format,
                 } => {
                     let local = crev_lib::Local::auto_create_or_open()?;
                     let db = local.load_db()?;
-                    let for_id = local.get_for_id_from_str(OptionDeref::as_deref(&for_id))?;
-                    let trust_set = db.calculate_trust_set(&for_id, &trust_params.into());
+                    match output_format {
+                        opts::IdOutputFormat::Text => {
+                            let for_id =
+                                local.get_for_id_from_str(OptionDeref::as_deref(&for_id))?;
+                            let trust_set = db.calculate_trust_set(&for_id, &trust_params.into());
 
-                    print_ids(db.all_known_ids().iter(), &trust_set, &db)?;
+                            print_ids(db.all_known_ids().iter(), &trust_set, &db)?;
+                        }
+                        opts::IdOutputFormat::Dot => print_trust_graph_dot(&db)?,
+                    }
                 }
             },
         },
@@ -160,18 +452,32 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 let status = run_diff(&args)?;
                 std::process::exit(status.code().unwrap_or(-159));
             }
-            opts::Crate::Verify(args) => {
+            opts::Crate::Verify(mut args) => {
+                let mut config = load_verify_config(&args.common.cargo_opts)?;
+                if let Some(name) = &args.profile_name {
+                    config = config.with_profile(name)?;
+                }
+                args.apply_config_defaults(&config)?;
+                if args.post_verify_hook.is_none() {
+                    args.post_verify_hook = load_post_verify_hook()?;
+                }
                 return if args.interactive {
-                    tui::verify_deps(args)
+                    tui::verify_deps(*args)
+                } else if args.watch {
+                    deps::watch::verify_deps_watch(*args)
                 } else {
-                    deps::verify_deps(args)
+                    deps::verify_deps(*args)
                 };
             }
             opts::Crate::Mvp(args) => {
                 deps::crate_mvps(args)?;
             }
             opts::Crate::Goto(args) => {
-                goto_crate_src(&args.crate_)?;
+                if args.list {
+                    list_goto_sessions(args.clean_stale)?;
+                } else {
+                    goto_crate_src(&args.common.crate_)?;
+                }
             }
             opts::Crate::Open(args) => {
                 handle_goto_mode_command(&args.common.clone(), |sel| {
@@ -179,16 +485,19 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 })?;
             }
             opts::Crate::Clean(args) => {
-                if args.crate_.is_empty() && are_we_called_from_goto_shell().is_none() {
-                    clean_all_unclean_crates()?;
+                if args.common.crate_.is_empty() && are_we_called_from_goto_shell().is_none() {
+                    clean_all_unclean_crates(args.dry_run)?;
                 } else {
-                    handle_goto_mode_command(&args, |sel| clean_crate(sel))?;
+                    handle_goto_mode_command(&args.common, |sel| clean_crate(sel, args.dry_run))?;
                 }
             }
-            opts::Crate::Dir(args) => show_dir(&args.common.crate_)?,
+            opts::Crate::Dir(args) => show_dir(&args.common.crate_, args.digest)?,
 
             opts::Crate::Review(args) => {
-                handle_goto_mode_command(&args.common, |sel| {
+                if args.withdraw {
+                    bail!("`--withdraw` can only be used with `crate unreview`");
+                }
+                handle_review_command(&args, |sel| {
                     let is_advisory = args.advisory
                         || args.affected.is_some()
                         || (!args.issue && args.severity.is_some());
@@ -215,14 +524,22 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                             TrustProofType::Trust
                         },
                         &args.common_proof_create,
+                        args.print_only,
                         &args.diff,
+                        args.diff_from_trusted,
                         args.skip_activity_check || is_advisory || args.issue,
                         args.cargo_opts.clone(),
                     )
                 })?;
             }
             opts::Crate::Unreview(args) => {
-                handle_goto_mode_command(&args.common, |sel| {
+                if args.withdraw {
+                    handle_review_command(&args, |sel| {
+                        withdraw_review(sel, &args.common_proof_create, args.cargo_opts.clone())
+                    })?;
+                    return Ok(CommandExitStatus::Success);
+                }
+                handle_review_command(&args, |sel| {
                     let is_advisory = args.advisory
                         || args.affected.is_some()
                         || (!args.issue && args.severity.is_some());
@@ -249,14 +566,16 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                             TrustProofType::Untrust
                         },
                         &args.common_proof_create,
+                        args.print_only,
                         &args.diff,
+                        args.diff_from_trusted,
                         args.skip_activity_check || is_advisory || args.issue,
                         args.cargo_opts.clone(),
                     )
                 })?;
             }
             opts::Crate::Search(args) => {
-                lookup_crates(&args.query, args.count)?;
+                lookup_crates(&args.query, args.count, args.sort)?;
             }
         },
         opts::Command::Config(args) => match args {
@@ -264,6 +583,16 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 let local = crev_lib::Local::auto_create_or_open()?;
                 local.edit_user_config()?;
             }
+            opts::Config::Get { key } => {
+                let local = crev_lib::Local::auto_create_or_open()?;
+                if let Some(value) = local.get_user_config_value(&key)? {
+                    println!("{}", value);
+                }
+            }
+            opts::Config::Set { key, value } => {
+                let local = crev_lib::Local::auto_create_or_open()?;
+                local.set_user_config_value(&key, &value)?;
+            }
             opts::Config::Completions { shell } => {
                 use structopt::clap::Shell;
                 let shell = match shell
@@ -285,14 +614,41 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                         bail!("{} shell not supported", other);
                     }
                 };
+                let mut script = vec![];
                 opts::Opts::clap().gen_completions_to(
                     // we have to pretend, we're generating for main cargo binary
                     "cargo",
                     shell,
-                    &mut std::io::stdout(),
+                    &mut script,
+                );
+                print!(
+                    "{}",
+                    add_crate_name_completions(shell, String::from_utf8(script)?)
                 );
             }
         },
+        opts::Command::CompleteCrates => {
+            let repo = Repo::auto_open_cwd_default()?;
+            let mut names = std::collections::BTreeSet::new();
+            repo.for_every_non_local_dep_crate_id(|pkg_id| {
+                names.insert(pkg_id.name().to_string());
+                Ok(())
+            })?;
+            for name in names {
+                println!("{}", name);
+            }
+        }
+        opts::Command::Proof(args) => match args {
+            opts::Proof::Verify(args) => {
+                return verify_proofs(OptionDeref::as_deref(&args.path));
+            }
+            opts::Proof::Inspect(args) => {
+                return inspect_proofs(&args.path, args.show_signature);
+            }
+        },
+        opts::Command::Db(args) => match args {
+            opts::Db::Stats { output_format } => print_db_stats(output_format)?,
+        },
         opts::Command::Repo(args) => match args {
             opts::Repo::Git(git) => {
                 let local = Local::auto_open()?;
@@ -300,20 +656,23 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 std::process::exit(status.code().unwrap_or(-159));
             }
             opts::Repo::Query(args) => match args {
-                opts::RepoQuery::Review(args) => list_reviews(&args.crate_)?,
-                opts::RepoQuery::Advisory(args) => list_advisories(&args.crate_)?,
+                opts::RepoQuery::Review(args) => list_reviews(&args.crate_, args.output_format)?,
+                opts::RepoQuery::Advisory(args) => {
+                    list_advisories(&args.crate_, args.output_format)?
+                }
                 opts::RepoQuery::Issue(args) => list_issues(&args)?,
             },
-            opts::Repo::Publish => {
+            opts::Repo::Publish(args) => {
                 let local = Local::auto_open()?;
                 let mut status = local.run_git(vec!["diff".into(), "--exit-code".into()])?;
 
                 if status.code().unwrap_or(-2) == 1 {
+                    let commit_msg = get_publish_commit_message(&local, &args)?;
                     status = local.run_git(vec![
                         "commit".into(),
                         "-a".into(),
                         "-m".into(),
-                        "auto-commit on `crev publish`".into(),
+                        commit_msg.into(),
                     ])?;
                 }
 
@@ -329,9 +688,14 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 opts::RepoFetch::Trusted {
                     distance_params,
                     for_id,
+                    prune,
                 } => {
                     let local = Local::auto_create_or_open()?;
-                    local.fetch_trusted(distance_params.into(), OptionDeref::as_deref(&for_id))?;
+                    local.fetch_trusted(
+                        distance_params.into(),
+                        OptionDeref::as_deref(&for_id),
+                        prune,
+                    )?;
                 }
                 opts::RepoFetch::Url(params) => {
                     let local = Local::auto_create_or_open()?;
@@ -342,6 +706,19 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                     local.fetch_all()?;
                 }
             },
+            opts::Repo::VerifyIntegrity(args) => {
+                let url = if args.all {
+                    None
+                } else {
+                    OptionDeref::as_deref(&args.url)
+                };
+                return verify_repos_integrity(url);
+            }
+            opts::Repo::CloneProofs(params) => {
+                let local = Local::auto_create_or_open()?;
+                let dir = local.clone_remote_git(&params.url)?;
+                println!("{}", dir.display());
+            }
             opts::Repo::Update(args) => {
                 let local = Local::auto_open()?;
                 let status = local.run_git(vec!["pull".into(), "--rebase".into()])?;
@@ -352,6 +729,26 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 repo.update_source()?;
                 repo.update_counts()?;
             }
+            opts::Repo::Sync(args) => {
+                let local = Local::auto_create_or_open()?;
+                eprintln!("Fetching all proof repositories...");
+                local.fetch_all()?;
+                if !args.no_own_repo {
+                    eprintln!("Pulling the own proof repository...");
+                    let status = local.run_git(vec!["pull".into(), "--rebase".into()])?;
+                    if !status.success() {
+                        std::process::exit(status.code().unwrap_or(-159));
+                    }
+                }
+                let repo = Repo::auto_open_cwd(args.cargo_opts)?;
+                eprintln!("Updating the crates.io index...");
+                repo.update_source()?;
+                eprintln!("Updating download counts...");
+                repo.update_counts()?;
+                eprintln!("Indexing proofs...");
+                local.load_db()?;
+                eprintln!("Done.");
+            }
             opts::Repo::Edit(cmd) => match cmd {
                 opts::RepoEdit::Readme => {
                     let local = crev_lib::Local::auto_open()?;
@@ -360,11 +757,17 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                 opts::RepoEdit::Known => {
                     edit_known_owners_list()?;
                 }
+                opts::RepoEdit::LastProof(args) => {
+                    edit_last_proof(args.no_commit)?;
+                }
             },
 
+            opts::Repo::ImportAdvisoryDb(args) => {
+                advisory_db::import_advisory_db(&args)?;
+            }
             opts::Repo::Import(args) => {
                 let local = Local::auto_create_or_open()?;
-                let id = local.read_current_unlocked_id(&crev_common::read_passphrase)?;
+                let signer = local.read_current_signer(&crev_common::read_passphrase)?;
 
                 let s = load_stdin_with_prompt()?;
                 let proofs = crev_data::proof::Proof::parse(s.as_slice())?;
@@ -375,8 +778,8 @@ fn run_command(command: opts::Command) -> Result<CommandExitStatus> {
                     if args.reset_date {
                         content.set_date(&crev_common::now());
                     }
-                    content.set_author(&id.as_pubid());
-                    let proof = content.sign_by(&id)?;
+                    content.set_author(signer.as_pubid());
+                    let proof = signer.sign(&content)?;
                     maybe_store(&local, &proof, &commit_msg, &args.common)?;
                 }
             }
@@ -398,11 +801,32 @@ fn load_stdin_with_prompt() -> Result<Vec<u8>> {
     Ok(s)
 }
 
+fn init_logger(format: opts::TextOrJson) {
+    let mut builder = env_logger::Builder::from_default_env();
+    if format == opts::TextOrJson::Json {
+        builder.format(|buf, record| {
+            writeln!(
+                buf,
+                "{}",
+                serde_json::json!({
+                    "level": record.level().to_string(),
+                    "target": record.target(),
+                    "message": record.args().to_string(),
+                })
+            )
+        });
+    }
+    builder.init();
+}
+
 fn main() {
-    env_logger::init();
     let opts = opts::Opts::from_args();
+    init_logger(opts.log_format);
+    if opts.no_color {
+        std::env::set_var(term::NO_COLOR_ENV, "1");
+    }
     let opts::MainCommand::Crev(command) = opts.command;
-    match run_command(command) {
+    match run_command(command, opts.proof_dir, opts.rebuild_index) {
         Ok(CommandExitStatus::Success) => {}
         Ok(CommandExitStatus::VerificationFailed) => std::process::exit(-1),
         Err(e) => {
@@ -1,6 +1,6 @@
 use common_failures::Result;
 use crev_data::Level;
-use failure::bail;
+use failure::{bail, format_err};
 use semver::Version;
 use std::{ffi::OsString, path::PathBuf};
 use structopt::StructOpt;
@@ -27,6 +27,30 @@ impl CrateSelector {
 
         Ok(())
     }
+
+    /// Parse a crate given as `name`, `name@version` or `name version`
+    pub fn from_spec(spec: &str, unrelated: bool) -> Result<Self> {
+        let mut parts = spec
+            .trim()
+            .splitn(2, |c: char| c == '@' || c.is_whitespace());
+        let name = parts.next().unwrap_or("").trim();
+        if name.is_empty() {
+            bail!("Crate name missing in `{}`", spec);
+        }
+        let version = match parts.next().map(str::trim) {
+            Some(version) if !version.is_empty() => Some(
+                Version::parse(version)
+                    .map_err(|e| format_err!("Invalid version in `{}`: {}", spec, e))?,
+            ),
+            _ => None,
+        };
+
+        Ok(Self {
+            unrelated,
+            name: Some(name.to_owned()),
+            version,
+        })
+    }
 }
 
 #[derive(Debug, StructOpt, Clone, Default)]
@@ -56,6 +80,40 @@ pub struct CargoOpts {
     /// [cargo] Skip targets other than specified (no value = autodetect)
     #[structopt(long = "target")]
     pub target: Option<Option<String>>,
+
+    /// [cargo] Profile of the build to match: `release` skips dev dependencies, like
+    /// `cargo build --release` [default: dev]
+    #[structopt(
+        long = "profile",
+        value_name = "PROFILE",
+        raw(possible_values = r#"&["dev", "release"]"#)
+    )]
+    pub profile: Option<CargoProfile>,
+}
+
+impl CargoOpts {
+    /// Leave dev dependencies out of the resolved crates
+    pub fn skip_dev_dependencies(&self) -> bool {
+        self.no_dev_dependencies || self.profile == Some(CargoProfile::Release)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CargoProfile {
+    Dev,
+    Release,
+}
+
+impl std::str::FromStr for CargoProfile {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "dev" => CargoProfile::Dev,
+            "release" => CargoProfile::Release,
+            _ => bail!("Unknown profile: {}", s),
+        })
+    }
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -69,6 +127,21 @@ pub struct IdNew {
     #[structopt(long = "https-push")]
     /// Setup `https` instead of recommended `ssh`-based push url
     pub use_https_push: bool,
+    #[structopt(
+        long = "from-seed",
+        value_name = "HEX",
+        parse(try_from_str = "parse_hex_seed")
+    )]
+    /// Derive the Id from a hex-encoded seed instead of randomly. INSECURE, for tests only
+    pub from_seed: Option<Vec<u8>>,
+}
+
+fn parse_hex_seed(s: &str) -> Result<Vec<u8>> {
+    let seed = hex::decode(s).map_err(|e| format_err!("Invalid hex seed: {}", e))?;
+    if seed.is_empty() {
+        bail!("Seed can't be empty");
+    }
+    Ok(seed)
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -77,6 +150,47 @@ pub struct IdSwitch {
     pub id: String,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+pub struct IdRenameOwner {
+    /// Old crates.io login of the owner
+    pub from: String,
+    /// New crates.io login of the owner
+    pub to: String,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct IdFingerprint {
+    /// Public Id (default: current Id)
+    pub id: Option<String>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct IdListRepos {
+    /// Output format
+    #[structopt(
+        long = "output-format",
+        default_value = "text",
+        raw(possible_values = r#"&["text", "json"]"#)
+    )]
+    pub output_format: TextOrJson,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct IdMigrate {
+    /// Own Id the proofs were signed with
+    pub from: String,
+
+    /// Own Id to re-sign the proofs with; must be the current Id (default: current Id)
+    pub to: Option<String>,
+
+    /// Only print how many proofs would be migrated
+    #[structopt(long = "dry-run")]
+    pub dry_run: bool,
+
+    #[structopt(flatten)]
+    pub common_proof_create: CommonProofCreate,
+}
+
 /// Parameters describing trust graph traversal
 #[derive(Debug, StructOpt, Clone, Default)]
 pub struct TrustDistanceParams {
@@ -122,6 +236,18 @@ pub struct Diff {
     #[structopt(flatten)]
     pub trust_params: TrustDistanceParams,
 
+    /// Print only a summary of changed files, like `git diff --stat`
+    #[structopt(long = "stat")]
+    pub stat: bool,
+
+    /// Show the diff with this command (eg. `difft`), given the two source directories [default: `diff-cmd` from the config]
+    #[structopt(
+        long = "diff-command",
+        value_name = "CMD",
+        raw(conflicts_with = r#""stat""#)
+    )]
+    pub diff_command: Option<String>,
+
     /// Crate name
     pub name: String,
 
@@ -132,35 +258,53 @@ pub struct Diff {
 
 #[derive(Debug, StructOpt, Clone, Default)]
 pub struct TrustLevelRequirements {
-    /// Minimum trust level required
-    #[structopt(long = "trust", default_value = "low")]
-    pub trust_level: crev_data::Level,
+    /// Minimum trust level required [default: low]
+    #[structopt(
+        long = "trust",
+        raw(possible_values = r#"&["none", "low", "medium", "high"]"#)
+    )]
+    pub trust_level: Option<crev_data::Level>,
+}
+
+impl TrustLevelRequirements {
+    pub fn level(&self) -> Level {
+        self.trust_level.unwrap_or(Level::Low)
+    }
 }
 
 /// Verification Requirements
+///
+/// Not given values are `None`, so `crate verify` can take them from the config.
 #[derive(Debug, StructOpt, Clone, Default)]
 pub struct VerificationRequirements {
     #[structopt(flatten)]
     pub trust_level: TrustLevelRequirements,
 
-    /// Number of reviews required
-    #[structopt(long = "redundancy", default_value = "1")]
-    pub redundancy: u64,
-    /// Required understanding
-    #[structopt(long = "understanding", default_value = "none")]
-    pub understanding_level: Level,
-    /// Required thoroughness
-    #[structopt(long = "thoroughness", default_value = "none")]
-    pub thoroughness_level: Level,
+    /// Number of reviews required [default: 1]
+    #[structopt(long = "redundancy")]
+    pub redundancy: Option<u64>,
+    /// Required understanding [default: none]
+    #[structopt(
+        long = "understanding",
+        raw(possible_values = r#"&["none", "low", "medium", "high"]"#)
+    )]
+    pub understanding_level: Option<Level>,
+    /// Required thoroughness [default: none]
+    #[structopt(
+        long = "thoroughness",
+        raw(possible_values = r#"&["none", "low", "medium", "high"]"#)
+    )]
+    pub thoroughness_level: Option<Level>,
 }
 
 impl From<VerificationRequirements> for crev_lib::VerificationRequirements {
     fn from(req: VerificationRequirements) -> Self {
         crev_lib::VerificationRequirements {
-            trust_level: req.trust_level.trust_level,
-            redundancy: req.redundancy,
-            understanding: req.understanding_level,
-            thoroughness: req.thoroughness_level,
+            trust_level: req.trust_level.level(),
+            redundancy: req.redundancy.unwrap_or(1),
+            understanding: req.understanding_level.unwrap_or(Level::None),
+            thoroughness: req.thoroughness_level.unwrap_or(Level::None),
+            since: None,
         }
     }
 }
@@ -171,6 +315,27 @@ pub struct Update {
     pub cargo_opts: CargoOpts,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+pub struct RepoVerifyIntegrity {
+    /// Url of the fetched proof repository to check
+    #[structopt(raw(required_unless = r#""all""#))]
+    pub url: Option<String>,
+
+    /// Check all the fetched proof repositories
+    #[structopt(long = "all", raw(conflicts_with = r#""url""#))]
+    pub all: bool,
+}
+
+#[derive(Debug, StructOpt, Clone, Default)]
+pub struct RepoSync {
+    /// Don't pull the own proof repository
+    #[structopt(long = "no-own-repo")]
+    pub no_own_repo: bool,
+
+    #[structopt(flatten)]
+    pub cargo_opts: CargoOpts,
+}
+
 #[derive(Debug, StructOpt, Clone, Default)]
 pub struct CrateVerifyCommon {
     #[structopt(flatten)]
@@ -199,6 +364,10 @@ pub struct CrateVerify {
     /// Display more informations about the crates
     pub verbose: bool,
 
+    #[structopt(long = "no-verbose", raw(overrides_with = r#""verbose""#))]
+    /// Don't display more informations about the crates, even if set in the config
+    pub no_verbose: bool,
+
     #[structopt(long = "interactive", short = "i")]
     pub interactive: bool,
 
@@ -206,17 +375,452 @@ pub struct CrateVerify {
     /// Display only crates not passing the verification
     pub skip_verified: bool,
 
+    #[structopt(long = "no-skip-verified", raw(overrides_with = r#""skip_verified""#))]
+    /// Display verified crates too, even if skipped in the config
+    pub no_skip_verified: bool,
+
     #[structopt(long = "skip-known-owners")]
     /// Skip crate from known owners (use `edit known` to edit the list)
     pub skip_known_owners: bool,
 
+    #[structopt(
+        long = "no-skip-known-owners",
+        raw(overrides_with = r#""skip_known_owners""#)
+    )]
+    /// Don't skip crates from known owners, even if skipped in the config
+    pub no_skip_known_owners: bool,
+
     #[structopt(long = "skip-indirect")]
     /// Skip dependencies that are not direct
     pub skip_indirect: bool,
 
+    #[structopt(long = "no-skip-indirect", raw(overrides_with = r#""skip_indirect""#))]
+    /// Don't skip dependencies that are not direct, even if skipped in the config
+    pub no_skip_indirect: bool,
+
     #[structopt(long = "recursive")]
     /// Calculate recursive metrics for your packages
     pub recursive: bool,
+
+    #[structopt(long = "no-recursive", raw(overrides_with = r#""recursive""#))]
+    /// Don't calculate recursive metrics, even if set in the config
+    pub no_recursive: bool,
+
+    #[structopt(long = "skip-geiger")]
+    /// Don't count `unsafe` lines (geiger count), which is slow on big crates
+    pub skip_geiger: bool,
+
+    #[structopt(long = "no-skip-geiger", raw(overrides_with = r#""skip_geiger""#))]
+    /// Count `unsafe` lines, even if skipped in the config
+    pub no_skip_geiger: bool,
+
+    #[structopt(long = "jobs", short = "j", value_name = "N")]
+    /// Number of crates to scan in parallel [default: number of CPUs]
+    pub jobs: Option<usize>,
+
+    /// Extra columns to display, comma-separated (same as the `--show-<column>` flags)
+    #[structopt(
+        long = "columns",
+        value_name = "COLUMNS",
+        raw(
+            use_delimiter = "true",
+            possible_values = r#"&["review-date", "owners", "review-sources"]"#
+        )
+    )]
+    pub columns: Vec<VerifyColumn>,
+
+    #[structopt(long = "no-accumulate")]
+    /// Display issues, lines, geiger count and flags of each crate alone, even with `--recursive`
+    pub no_accumulate: bool,
+
+    #[structopt(long = "recursive-depth", value_name = "N")]
+    /// Like `--recursive`, but only up to N levels below the direct dependencies (0 - direct dependencies only)
+    pub recursive_depth: Option<u64>,
+
+    /// Which crates get a row: `all`, `direct` dependencies only, or
+    /// `transitive-only` - one row per direct dependency, summarizing it with all its dependencies
+    #[structopt(
+        long = "rows",
+        default_value = "all",
+        raw(possible_values = r#"&["all", "direct", "transitive-only"]"#)
+    )]
+    pub rows: VerifyRows,
+
+    /// Order of the rows; ascending, with unknown values last
+    #[structopt(
+        long = "sort",
+        raw(possible_values = r#"&["name", "geiger", "loc", "reviews", "trust", "downloads"]"#)
+    )]
+    pub sort: Option<VerifySort>,
+
+    /// Sort in descending order, eg. to show the biggest crates first
+    #[structopt(long = "reverse", raw(requires = r#""sort""#))]
+    pub reverse: bool,
+
+    /// Print the rows in sections by trust: verified, low-trust, none, distrusted (and failed)
+    #[structopt(long = "group-by-trust")]
+    pub group_by_trust: bool,
+
+    #[structopt(long = "max-depth-path")]
+    /// Print the deepest dependency chain and the number of crates at each depth
+    pub max_depth_path: bool,
+
+    #[structopt(long = "timings")]
+    /// Print the time spent scanning each crate, slowest first
+    pub timings: bool,
+
+    #[structopt(long = "redownload")]
+    /// Wipe and re-download crates with an unclean digest before verifying them
+    pub redownload: bool,
+
+    #[structopt(long = "baseline")]
+    /// Git ref of the `Cargo.lock` to compare with; only new or bumped dependencies fail the verification
+    pub baseline: Option<String>,
+
+    #[structopt(long = "prune-unreviewed", raw(conflicts_with = r#""stdin""#))]
+    /// Don't verify dependencies of crates that have no trusted reviews; they are unverified anyway
+    pub prune_unreviewed: bool,
+
+    #[structopt(long = "only-changed", raw(conflicts_with = r#""stdin""#))]
+    /// Verify only crates added or bumped in `Cargo.lock` since the last commit
+    pub only_changed: bool,
+
+    #[structopt(long = "fail-on-issue")]
+    /// Fail the verification if any crate has issues reported by trusted reviewers
+    pub fail_on_issue: bool,
+
+    #[structopt(long = "show-review-date")]
+    /// Display the date of the most recent trusted review of each crate
+    pub show_review_date: bool,
+
+    #[structopt(long = "stale-after", value_name = "DAYS")]
+    /// Highlight trusted reviews older than this many days (implies `--show-review-date`)
+    pub stale_after: Option<i64>,
+
+    #[structopt(long = "trust-file", parse(from_os_str))]
+    /// Use proofs from this file in addition to the local ones, for this run only
+    pub trust_file: Option<PathBuf>,
+
+    #[structopt(long = "show-owners")]
+    /// Display owners of each crate, highlighting known owners
+    pub show_owners: bool,
+
+    #[structopt(long = "show-review-sources")]
+    /// Display proof repositories of the trusted reviewers of each crate
+    pub show_review_sources: bool,
+
+    #[structopt(long = "only-with-issues")]
+    /// Display only crates with issues reported by trusted reviewers, and the ids of these issues
+    pub only_with_issues: bool,
+
+    #[structopt(long = "annotate-advisories")]
+    /// Warn about advisories from trusted reviewers affecting each crate
+    pub annotate_advisories: bool,
+
+    #[structopt(long = "owners-from", value_name = "PATH", parse(from_os_str))]
+    /// Also treat the owners listed in this file (one per line) as known
+    pub owners_from: Option<PathBuf>,
+
+    #[structopt(long = "profile-name", value_name = "NAME")]
+    /// Use the flags saved under `verify.profiles.<NAME>` in the config
+    pub profile_name: Option<String>,
+
+    /// Output format; `sarif` prints a report for code scanning tools, `markdown` a table to paste in pull requests, `json` an array of crates
+    #[structopt(
+        long = "output-format",
+        default_value = "text",
+        raw(possible_values = r#"&["text", "sarif", "markdown", "json"]"#)
+    )]
+    pub output_format: VerifyOutputFormat,
+
+    #[structopt(long = "count-only")]
+    /// Print only the numbers of crates: total, verified, unverified, unclean and with issues (as an object with `--output-format json`)
+    pub count_only: bool,
+
+    #[structopt(long = "json-lines-to", value_name = "PATH", parse(from_os_str))]
+    /// Also append a JSON object per crate to this file, as soon as it's scanned
+    pub json_lines_to: Option<PathBuf>,
+
+    #[structopt(long = "report-to", value_name = "URL")]
+    /// POST the results as JSON to this URL, eg. of a dashboard; failing to doesn't fail the verification
+    pub report_to: Option<String>,
+
+    #[structopt(long = "post-verify-hook", value_name = "CMD")]
+    /// Shell command to run at the end, getting the results as JSON on stdin and `passed` or `failed` as `$1` [default: `post-verify-hook` from the user config]
+    pub post_verify_hook: Option<String>,
+
+    #[structopt(long = "hook-affects-exit")]
+    /// Fail the verification if the post-verify hook fails
+    pub hook_affects_exit: bool,
+
+    #[structopt(long = "ignore", value_name = "PATTERN", number_of_values = 1)]
+    /// Don't require reviews of crates matching `name` or `name=<version range>`, in addition to `.crev/ignore`
+    pub ignore: Vec<crate::deps::IgnorePattern>,
+
+    #[structopt(long = "exclude", value_name = "PATTERN", number_of_values = 1)]
+    /// Don't verify nor display crates matching `name` or `name@<version range>`; `*` and `?` match any characters
+    pub exclude: Vec<crate::deps::IgnorePattern>,
+
+    #[structopt(
+        long = "since",
+        value_name = "DATE",
+        parse(try_from_str = "parse_date")
+    )]
+    /// Count only reviews created since this date, given as `YYYY-MM-DD` or RFC 3339
+    pub since: Option<crev_data::proof::Date>,
+
+    #[structopt(long = "require-reviewers", value_name = "N")]
+    /// Require reviews verifying each crate from at least N independent reviewers (with distinct proof repositories)
+    pub require_reviewers: Option<u64>,
+
+    #[structopt(long = "max-geiger", value_name = "N")]
+    /// Fail the verification if any crate has a geiger count (`unsafe` lines) over N
+    pub max_geiger: Option<u64>,
+
+    #[structopt(long = "min-downloads", value_name = "N")]
+    /// Warn about crates downloaded fewer than N times (all versions together)
+    pub min_downloads: Option<u64>,
+
+    #[structopt(long = "fail-on-low-downloads", raw(requires = r#""min_downloads""#))]
+    /// Fail the verification if any crate is under `--min-downloads`
+    pub fail_on_low_downloads: bool,
+
+    #[structopt(long = "budget", value_name = "PATH", parse(from_os_str))]
+    /// Check the crates against the thresholds (`max-geiger`, `min-downloads`, `min-reviewers`, `max-issues`, `max-loc`) in this TOML file
+    pub budget: Option<PathBuf>,
+
+    #[structopt(long = "stdin")]
+    /// Verify crates listed on stdin, one `name@version` per line, instead of the dependencies of the current project
+    pub stdin: bool,
+
+    #[structopt(
+        long = "watch",
+        raw(conflicts_with_all = r#"&["interactive", "stdin"]"#)
+    )]
+    /// Verify again each time `Cargo.toml` or `Cargo.lock` changes, until interrupted
+    pub watch: bool,
+
+    #[structopt(long = "api-retries", value_name = "N")]
+    /// Retry failed crates.io API requests up to N times [default: 2]
+    pub api_retries: Option<u32>,
+
+    #[structopt(long = "crates-io-api", value_name = "URL")]
+    /// Base URL of the crates.io API, eg. of a mirror [default: `crates-io-api` from the config, or crates.io]
+    pub crates_io_api: Option<String>,
+
+    #[structopt(long = "width", value_name = "N")]
+    /// Fit the table in N columns [default: width of the terminal]
+    pub width: Option<usize>,
+
+    #[structopt(long = "explain", value_name = "CRATE")]
+    /// Explain why a crate is or isn't verified, instead of verifying all of them
+    pub explain: Option<String>,
+
+    #[structopt(
+        long = "review-missing-interactive",
+        raw(conflicts_with_all = r#"&["interactive", "watch", "stdin"]"#)
+    )]
+    /// After verifying, offer to review each unverified crate in turn (only when stdin is a terminal)
+    pub review_missing_interactive: bool,
+
+    #[structopt(
+        long = "review-diffs-interactive",
+        raw(
+            conflicts_with_all = r#"&["interactive", "watch", "stdin", "review_missing_interactive"]"#
+        )
+    )]
+    /// After verifying, offer to diff and review each unverified crate that has an older trusted version (only when stdin is a terminal)
+    pub review_diffs_interactive: bool,
+}
+
+/// Parse a date given as `YYYY-MM-DD` (midnight UTC), or in RFC 3339 format
+fn parse_date(s: &str) -> Result<crev_data::proof::Date> {
+    if let Ok(date) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(date);
+    }
+    let date = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format_err!("Invalid date `{}`, expected YYYY-MM-DD or RFC 3339", s))?;
+    Ok(chrono::DateTime::from_utc(
+        date.and_hms(0, 0, 0),
+        chrono::FixedOffset::east(0),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifySort {
+    Name,
+    Geiger,
+    Loc,
+    Reviews,
+    Trust,
+    Downloads,
+}
+
+impl std::str::FromStr for VerifySort {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "name" => VerifySort::Name,
+            "geiger" => VerifySort::Geiger,
+            "loc" => VerifySort::Loc,
+            "reviews" => VerifySort::Reviews,
+            "trust" => VerifySort::Trust,
+            "downloads" => VerifySort::Downloads,
+            _ => bail!("Unknown sort order: {}", s),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyRows {
+    All,
+    Direct,
+    TransitiveOnly,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for VerifyRows {
+    fn default() -> Self {
+        VerifyRows::All
+    }
+}
+
+impl std::str::FromStr for VerifyRows {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "all" => VerifyRows::All,
+            "direct" => VerifyRows::Direct,
+            "transitive-only" => VerifyRows::TransitiveOnly,
+            _ => bail!("Unknown rows mode: {}", s),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyOutputFormat {
+    Text,
+    Sarif,
+    Markdown,
+    Json,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for VerifyOutputFormat {
+    fn default() -> Self {
+        VerifyOutputFormat::Text
+    }
+}
+
+impl std::str::FromStr for VerifyOutputFormat {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => VerifyOutputFormat::Text,
+            "sarif" => VerifyOutputFormat::Sarif,
+            "markdown" => VerifyOutputFormat::Markdown,
+            "json" => VerifyOutputFormat::Json,
+            _ => bail!("Unknown output format: {}", s),
+        })
+    }
+}
+
+/// Value of a flag that can be turned on with `--<flag>`, off with `--no-<flag>`,
+/// or otherwise set in the config
+fn flag_or_config(on: bool, off: bool, config: Option<bool>) -> bool {
+    on || (!off && config.unwrap_or(false))
+}
+
+impl CrateVerify {
+    /// Fill in flags and values set in the config, unless already given
+    ///
+    /// Precedence: command line, then the `--profile-name` profile,
+    /// then project `.crev/config.toml`, then user config, then built-in default.
+    pub fn apply_config_defaults(&mut self, config: &crev_lib::local::VerifyConfig) -> Result<()> {
+        self.verbose = flag_or_config(self.verbose, self.no_verbose, config.verbose);
+        self.skip_verified = flag_or_config(
+            self.skip_verified,
+            self.no_skip_verified,
+            config.skip_verified,
+        );
+        self.skip_known_owners = flag_or_config(
+            self.skip_known_owners,
+            self.no_skip_known_owners,
+            config.skip_known_owners,
+        );
+        self.skip_indirect = flag_or_config(
+            self.skip_indirect,
+            self.no_skip_indirect,
+            config.skip_indirect,
+        );
+        self.recursive = flag_or_config(self.recursive, self.no_recursive, config.recursive);
+        self.skip_geiger =
+            flag_or_config(self.skip_geiger, self.no_skip_geiger, config.skip_geiger);
+
+        let requirements = &mut self.common.requirements;
+        requirements.trust_level.trust_level =
+            requirements.trust_level.trust_level.or(config.trust);
+        requirements.redundancy = requirements.redundancy.or(config.redundancy);
+        requirements.understanding_level =
+            requirements.understanding_level.or(config.understanding);
+        requirements.thoroughness_level = requirements.thoroughness_level.or(config.thoroughness);
+        self.jobs = self.jobs.or(config.jobs);
+
+        if self.columns.is_empty() {
+            if let Some(ref columns) = config.columns {
+                self.columns = columns
+                    .iter()
+                    .map(|column| column.parse())
+                    .collect::<Result<_>>()?;
+            }
+        }
+        for column in &self.columns {
+            match column {
+                VerifyColumn::ReviewDate => self.show_review_date = true,
+                VerifyColumn::Owners => self.show_owners = true,
+                VerifyColumn::ReviewSources => self.show_review_sources = true,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Optional column of `crate verify`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyColumn {
+    ReviewDate,
+    Owners,
+    ReviewSources,
+}
+
+impl std::str::FromStr for VerifyColumn {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "review-date" => VerifyColumn::ReviewDate,
+            "owners" => VerifyColumn::Owners,
+            "review-sources" => VerifyColumn::ReviewSources,
+            _ => bail!("Unknown column: {}", s),
+        })
+    }
+}
+
+#[derive(Debug, StructOpt, Clone, Default)]
+pub struct CrateMvp {
+    #[structopt(flatten)]
+    pub common: CrateVerifyCommon,
+
+    #[structopt(long = "min-reviews", default_value = "1")]
+    /// Show only reviewers with at least this many reviews
+    pub min_reviews: u64,
+
+    #[structopt(long = "top")]
+    /// Show at most this many reviewers
+    pub top: Option<usize>,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -224,10 +828,42 @@ pub struct IdTrust {
     /// Public IDs to create Trust Proof for
     pub pub_ids: Vec<String>,
 
+    /// Trust level to set in the proof [default: medium]
+    #[structopt(long = "level", raw(possible_values = r#"&["low", "medium", "high"]"#))]
+    pub level: Option<Level>,
+
+    /// Comment to set in the proof, instead of editing it
+    #[structopt(long = "comment")]
+    pub comment: Option<String>,
+
+    /// Only print how the proof would change the trusted Ids, without saving it
+    #[structopt(long = "dry-run")]
+    pub dry_run: bool,
+
+    #[structopt(flatten)]
+    pub trust_params: TrustDistanceParams,
+
     #[structopt(flatten)]
     pub common_proof_create: CommonProofCreate,
 }
 
+impl IdTrust {
+    /// Trust parameters to show the changes with, if only doing a dry run
+    pub fn dry_run_params(&self) -> Option<TrustDistanceParams> {
+        if self.dry_run {
+            Some(self.trust_params.clone())
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct IdTrustRemove {
+    /// Public ID to remove own Trust Proofs for
+    pub pub_id: String,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub struct RepoFetchUrl {
     /// URL to public proof repository
@@ -244,6 +880,10 @@ pub enum RepoFetch {
 
         #[structopt(long = "for-id")]
         for_id: Option<String>,
+
+        /// Remove local copies of proof repositories with proofs only from Ids that are no longer trusted (not the ones fetched with `repo fetch url` or `repo clone-proofs`)
+        #[structopt(long = "prune")]
+        prune: bool,
     },
 
     #[structopt(name = "url", alias = "u")]
@@ -255,6 +895,64 @@ pub enum RepoFetch {
     All,
 }
 
+/// `text` or `json` output (or log) format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextOrJson {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for TextOrJson {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => TextOrJson::Text,
+            "json" => TextOrJson::Json,
+            _ => bail!("Unknown format: {}", s),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdOutputFormat {
+    Text,
+    Dot,
+}
+
+impl std::str::FromStr for IdOutputFormat {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => IdOutputFormat::Text,
+            "dot" => IdOutputFormat::Dot,
+            _ => bail!("Unknown output format: {}", s),
+        })
+    }
+}
+
+/// Output format of reviews listed by `repo query`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewOutputFormat {
+    Text,
+    Json,
+    Yaml,
+}
+
+impl std::str::FromStr for ReviewOutputFormat {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => ReviewOutputFormat::Text,
+            "json" => ReviewOutputFormat::Json,
+            "yaml" => ReviewOutputFormat::Yaml,
+            _ => bail!("Unknown output format: {}", s),
+        })
+    }
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub enum IdQuery {
     /// Show current Id
@@ -272,6 +970,14 @@ pub enum IdQuery {
 
         #[structopt(long = "for-id")]
         for_id: Option<String>,
+
+        /// Output format; `dot` prints the trust graph for Graphviz
+        #[structopt(
+            long = "output-format",
+            default_value = "text",
+            raw(possible_values = r#"&["text", "dot"]"#)
+        )]
+        output_format: IdOutputFormat,
     },
 
     /// Show own Ids
@@ -279,6 +985,10 @@ pub enum IdQuery {
     Own {
         #[structopt(flatten)]
         trust_params: TrustDistanceParams,
+
+        /// List all Ids stored locally with their status, without unlocking them
+        #[structopt(long = "include-locked")]
+        include_locked: bool,
     },
 
     /// List trusted ids
@@ -292,6 +1002,31 @@ pub enum IdQuery {
 
         #[structopt(flatten)]
         trust_level: TrustLevelRequirements,
+
+        /// Warn about trust cycles (eg. A trusts B, B trusts A) in the WoT
+        #[structopt(long = "warn-cycles")]
+        warn_cycles: bool,
+
+        /// Show the Ids through which each Id is trusted
+        #[structopt(long = "transitive-sources")]
+        transitive_sources: bool,
+    },
+
+    /// Show the chains of trust proofs that make an Id trusted
+    #[structopt(name = "explain-trust")]
+    ExplainTrust {
+        /// Id to explain the trust level of
+        id: String,
+
+        #[structopt(flatten)]
+        trust_params: TrustDistanceParams,
+
+        #[structopt(long = "for-id")]
+        for_id: Option<String>,
+
+        /// Show at most this many paths
+        #[structopt(long = "max-paths", default_value = "20")]
+        max_paths: usize,
     },
 }
 
@@ -299,12 +1034,28 @@ pub enum IdQuery {
 pub struct RepoQueryReview {
     #[structopt(flatten)]
     pub crate_: CrateSelector,
+
+    /// Output format
+    #[structopt(
+        long = "output-format",
+        default_value = "text",
+        raw(possible_values = r#"&["text", "json", "yaml"]"#)
+    )]
+    pub output_format: ReviewOutputFormat,
 }
 
 #[derive(Debug, StructOpt, Clone)]
 pub struct RepoQueryAdvisory {
     #[structopt(flatten)]
     pub crate_: CrateSelector,
+
+    /// Output format
+    #[structopt(
+        long = "output-format",
+        default_value = "text",
+        raw(possible_values = r#"&["text", "json", "yaml"]"#)
+    )]
+    pub output_format: ReviewOutputFormat,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -318,12 +1069,24 @@ pub struct RepoQueryIssue {
     /// Minimum trust level of the reviewers for reviews
     #[structopt(long = "trust", default_value = "none")]
     pub trust_level: crev_data::Level,
+
+    /// Output format
+    #[structopt(
+        long = "output-format",
+        default_value = "text",
+        raw(possible_values = r#"&["text", "json", "yaml"]"#)
+    )]
+    pub output_format: ReviewOutputFormat,
 }
 
 #[derive(Debug, StructOpt, Clone)]
 pub struct CrateDir {
     #[structopt(flatten)]
     pub common: ReviewOrGotoCommon,
+
+    /// Print the crev digest of the crate source instead of its directory
+    #[structopt(long = "digest")]
+    pub digest: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -350,6 +1113,17 @@ pub enum RepoEdit {
     /// Edit your KNOWN_CRATE_OWNERS.md file
     #[structopt(name = "known", alias = "k")]
     Known,
+
+    /// Edit your most recent proof (review, trust, ...), if it wasn't published yet
+    #[structopt(name = "last-proof")]
+    LastProof(RepoEditLastProof),
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct RepoEditLastProof {
+    /// Don't commit the edited proof
+    #[structopt(long = "no-commit")]
+    pub no_commit: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -359,12 +1133,36 @@ pub struct RepoGit {
     pub args: Vec<OsString>,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+pub struct CrateClean {
+    #[structopt(flatten)]
+    pub common: ReviewOrGotoCommon,
+
+    /// Only print the source directories that would be removed, with their sizes
+    #[structopt(long = "dry-run")]
+    pub dry_run: bool,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub struct ReviewOrGotoCommon {
     #[structopt(flatten)]
     pub crate_: CrateSelector,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+pub struct CrateGoto {
+    #[structopt(flatten)]
+    pub common: ReviewOrGotoCommon,
+
+    /// List `goto` shells that were started and not exited yet
+    #[structopt(long = "list")]
+    pub list: bool,
+
+    /// With `--list`, forget `goto` shells that are no longer running
+    #[structopt(long = "clean-stale", requires = "list")]
+    pub clean_stale: bool,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub struct CrateOpen {
     /// Shell command to execute with crate directory as an argument. Eg. "code --wait -n" for VSCode
@@ -379,7 +1177,7 @@ pub struct CrateOpen {
     pub common: ReviewOrGotoCommon,
 }
 
-#[derive(Debug, StructOpt, Clone)]
+#[derive(Debug, StructOpt, Clone, Default)]
 pub struct CommonProofCreate {
     /// Don't auto-commit local Proof Repository
     #[structopt(long = "no-commit")]
@@ -400,8 +1198,13 @@ pub struct CommonProofCreate {
 
 #[derive(Debug, StructOpt, Clone)]
 pub struct CrateReview {
-    #[structopt(flatten)]
-    pub common: ReviewOrGotoCommon,
+    /// This crate is not neccesarily a dependency of the current cargo project
+    #[structopt(long = "unrelated", short = "u")]
+    pub unrelated: bool,
+
+    /// Crate to review as `name [version]`, or many as `name` or `name@version`
+    #[structopt(name = "crates")]
+    pub crates: Vec<String>,
 
     #[structopt(flatten)]
     pub common_proof_create: CommonProofCreate,
@@ -425,12 +1228,87 @@ pub struct CrateReview {
     #[structopt(long = "skip-activity-check")]
     pub skip_activity_check: bool,
 
+    /// Withdraw your earlier positive review, without reporting anything negative (`unreview` only)
+    #[structopt(
+        long = "withdraw",
+        raw(
+            alias = r#""yank""#,
+            conflicts_with_all = r#"&["advisory", "affected", "severity", "issue", "diff", "diff_from_trusted"]"#
+        )
+    )]
+    pub withdraw: bool,
+
     #[structopt(long = "diff")]
     #[allow(clippy::option_option)]
     pub diff: Option<Option<semver::Version>>,
 
+    /// Review only the changes since the latest version trusted by you
+    #[structopt(long = "diff-from-trusted", conflicts_with = "diff")]
+    pub diff_from_trusted: bool,
+
     #[structopt(flatten)]
     pub cargo_opts: CargoOpts,
+
+    /// Review crates listed in a file, one `name@version` per line
+    #[structopt(long = "from-file", parse(from_os_str))]
+    pub from_file: Option<PathBuf>,
+
+    /// Print the proof that would be created, without editing, signing or storing it
+    #[structopt(long = "print-only", conflicts_with = "withdraw")]
+    pub print_only: bool,
+}
+
+impl CrateReview {
+    /// The crate to review, in the same form as other `crate` commands take it
+    ///
+    /// `None` if more than one crate was given.
+    pub fn single_crate(&self) -> Result<Option<ReviewOrGotoCommon>> {
+        if self.from_file.is_some() {
+            return Ok(None);
+        }
+        let crate_ = match self.crates.as_slice() {
+            [] => CrateSelector {
+                unrelated: self.unrelated,
+                ..Default::default()
+            },
+            [spec] => CrateSelector::from_spec(spec, self.unrelated)?,
+            [name, version] if !name.contains('@') && Version::parse(version).is_ok() => {
+                CrateSelector {
+                    unrelated: self.unrelated,
+                    name: Some(name.to_owned()),
+                    version: Some(Version::parse(version)?),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(ReviewOrGotoCommon { crate_ }))
+    }
+
+    /// All the crates to review, including the ones listed in `--from-file`
+    pub fn crate_selectors(&self) -> Result<Vec<CrateSelector>> {
+        if let Some(common) = self.single_crate()? {
+            return Ok(vec![common.crate_]);
+        }
+
+        let mut selectors = vec![];
+        for spec in &self.crates {
+            selectors.push(CrateSelector::from_spec(spec, self.unrelated)?);
+        }
+        if let Some(ref path) = self.from_file {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format_err!("Can't read {}: {}", path.display(), e))?;
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                selectors.push(CrateSelector::from_spec(line, self.unrelated)?);
+            }
+        }
+
+        Ok(selectors)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -440,11 +1318,44 @@ pub struct AdviseCommon {
     pub severity: Level,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSort {
+    Reviews,
+    Relevance,
+    Downloads,
+    RecentDownloads,
+    Newest,
+}
+
+impl std::str::FromStr for SearchSort {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "reviews" => SearchSort::Reviews,
+            "relevance" => SearchSort::Relevance,
+            "downloads" => SearchSort::Downloads,
+            "recent-downloads" => SearchSort::RecentDownloads,
+            "newest" => SearchSort::Newest,
+            _ => bail!("Unknown sort order: {}", s),
+        })
+    }
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub struct CrateSearch {
     /// Number of results
     #[structopt(long = "count", default_value = "10")]
     pub count: usize,
+    /// Order of the results
+    #[structopt(
+        long = "sort",
+        default_value = "reviews",
+        raw(
+            possible_values = r#"&["reviews", "relevance", "downloads", "recent-downloads", "newest"]"#
+        )
+    )]
+    pub sort: SearchSort,
     /// Query to use
     pub query: String,
 }
@@ -452,6 +1363,18 @@ pub struct CrateSearch {
 #[derive(Debug, StructOpt, Clone)]
 pub struct IdExport {
     pub id: Option<String>,
+
+    /// Wrap the exported Id in BEGIN/END markers with a checksum, safe to copy-paste
+    #[structopt(long = "armor")]
+    pub armor: bool,
+
+    /// Print the exported Id as a QR code, eg. to scan it with a phone
+    #[structopt(long = "qr")]
+    pub qr: bool,
+
+    /// Export only the public Id, for others to trust, instead of the (encrypted) private one
+    #[structopt(long = "public-only", raw(conflicts_with = r#""armor""#))]
+    pub public_only: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -464,6 +1387,24 @@ pub struct RepoImport {
     pub common: CommonProofCreate,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+pub struct RepoImportAdvisoryDb {
+    /// Local checkout of the advisory database to read, instead of fetching it
+    #[structopt(long = "path", value_name = "PATH", parse(from_os_str))]
+    pub path: Option<PathBuf>,
+
+    /// Import only the advisories of this crate (can be given more than once)
+    #[structopt(long = "crate", value_name = "NAME", number_of_values = 1)]
+    pub crate_: Vec<String>,
+
+    /// Own Id to sign the proofs with, instead of the current one
+    #[structopt(long = "id", value_name = "ID")]
+    pub id: Option<String>,
+
+    #[structopt(flatten)]
+    pub common: CommonProofCreate,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub enum Id {
     /// Create a new Id
@@ -482,10 +1423,30 @@ pub enum Id {
     #[structopt(name = "current", alias = "c")]
     Current,
 
+    /// Print the current Id and its url on one line, for scripts
+    #[structopt(name = "whoami")]
+    Whoami,
+
     /// Change current Id
     #[structopt(name = "switch", alias = "s")]
     Switch(IdSwitch),
 
+    /// Change the passphrase of your current Id
+    #[structopt(name = "passwd")]
+    Passwd,
+
+    /// Re-sign all proofs of an old own Id with the current one
+    #[structopt(name = "migrate")]
+    Migrate(IdMigrate),
+
+    /// Show a short fingerprint of an Id, to compare it out-of-band
+    #[structopt(name = "fingerprint")]
+    Fingerprint(IdFingerprint),
+
+    /// Rename a crates.io owner in your list of known owners, after they renamed their account
+    #[structopt(name = "rename-repo-owner")]
+    RenameOwner(IdRenameOwner),
+
     /// Trust an Id
     #[structopt(name = "trust", alias = "t")]
     Trust(IdTrust),
@@ -498,16 +1459,24 @@ pub enum Id {
     #[structopt(name = "distrust", alias = "d")]
     Distrust(IdTrust),
 
+    /// Remove own, not yet published, Trust Proofs for an Id
+    #[structopt(name = "trust-remove")]
+    TrustRemove(IdTrustRemove),
+
     /// Query Ids
     #[structopt(name = "query", alias = "q")]
     Query(IdQuery),
+
+    /// List fetched proof repositories, with the Ids publishing there
+    #[structopt(name = "list-repos")]
+    ListRepos(IdListRepos),
 }
 
 #[derive(Debug, StructOpt, Clone)]
 pub enum Crate {
     /// Start a shell in source directory of a crate under review
     #[structopt(name = "goto", alias = "g")]
-    Goto(ReviewOrGotoCommon),
+    Goto(CrateGoto),
 
     /// Open source code of a crate
     #[structopt(name = "open", alias = "o")]
@@ -515,7 +1484,7 @@ pub enum Crate {
 
     /// Clean a crate source code (eg. after review)
     #[structopt(name = "clean", alias = "c")]
-    Clean(ReviewOrGotoCommon),
+    Clean(CrateClean),
 
     /// Diff between two versions of a package
     #[structopt(name = "diff", alias = "d")]
@@ -548,15 +1517,18 @@ Recursive mode will will calculate most metrics for the crate together with all
 - geiger     - Geiger score: number of `unsafe` lines
 - flgs       - Flags for specific types of packages
   - CB         - Custom Build
+  - OC         - Owners Changed since the latest trusted version was reviewed
+- rvwrs      - Number of independent trusted reviewers (with `--require-reviewers`)
+- reviewed   - Date of the most recent trusted review (with `--show-review-date`)
 - name       - Crate name
 - version    - Crate version
 - latest_t   - Latest trusted version"
     )]
-    Verify(CrateVerify),
+    Verify(Box<CrateVerify>),
 
     /// Most valuable players (reviewers)
     #[structopt(name = "mvp", alias = "m")]
-    Mvp(CrateVerifyCommon),
+    Mvp(CrateMvp),
 
     /// Review a crate (code review, security advisory, flag issues)
     #[structopt(name = "review", alias = "r")]
@@ -571,12 +1543,55 @@ Recursive mode will will calculate most metrics for the crate together with all
     Search(CrateSearch),
 }
 
+// `CrateVerify` is much bigger than the other subcommands, so it's boxed, but
+// structopt 0.2 can derive subcommands only of types that implement these
+// (`augment_clap` and `is_subcommand` are inherent functions of derived types)
+impl StructOpt for Box<CrateVerify> {
+    fn clap<'a, 'b>() -> structopt::clap::App<'a, 'b> {
+        CrateVerify::clap()
+    }
+
+    fn from_clap(matches: &structopt::clap::ArgMatches) -> Self {
+        Box::new(CrateVerify::from_clap(matches))
+    }
+}
+
+trait BoxedSubcommand {
+    fn augment_clap<'a, 'b>(app: structopt::clap::App<'a, 'b>) -> structopt::clap::App<'a, 'b>;
+    fn is_subcommand() -> bool;
+}
+
+impl BoxedSubcommand for Box<CrateVerify> {
+    fn augment_clap<'a, 'b>(app: structopt::clap::App<'a, 'b>) -> structopt::clap::App<'a, 'b> {
+        CrateVerify::augment_clap(app)
+    }
+
+    fn is_subcommand() -> bool {
+        CrateVerify::is_subcommand()
+    }
+}
+
 #[derive(Debug, StructOpt, Clone)]
 pub enum Config {
     /// Edit the config file
     #[structopt(name = "edit", alias = "e")]
     Edit,
 
+    /// Print the value of a config key
+    #[structopt(name = "get")]
+    Get {
+        /// Key, eg. `open-cmd` or `verify.recursive`
+        key: String,
+    },
+
+    /// Set the value of a config key (an empty value unsets it)
+    #[structopt(name = "set")]
+    Set {
+        /// Key, eg. `open-cmd` or `verify.recursive`
+        key: String,
+        value: String,
+    },
+
     /// Completions
     #[structopt(name = "completions")]
     Completions {
@@ -585,18 +1600,33 @@ pub enum Config {
     },
 }
 
+#[derive(Debug, StructOpt, Clone)]
+pub struct RepoPublish {
+    /// Message for committing uncommitted proofs (default: `publish-message` from the config)
+    #[structopt(long = "message", short = "m")]
+    pub message: Option<String>,
+
+    /// List the crates of uncommitted reviews in the commit message
+    #[structopt(long = "with-crate-names")]
+    pub with_crate_names: bool,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Local Proof Repository
 pub enum Repo {
     // TODO: `Dir`
     /// Publish to remote repository
     #[structopt(name = "publish", alias = "p")]
-    Publish,
+    Publish(RepoPublish),
 
     /// Update data from online sources (proof repositories, crates.io)
     #[structopt(name = "update", alias = "pull")]
     Update(Update),
 
+    /// Fetch all proof repositories, update the own one and data from crates.io
+    #[structopt(name = "sync")]
+    Sync(RepoSync),
+
     /// Run raw git commands in the local proof repository
     #[structopt(name = "git", alias = "g")]
     #[structopt(raw(setting = "structopt::clap::AppSettings::TrailingVarArg"))]
@@ -611,6 +1641,10 @@ pub enum Repo {
     #[structopt(name = "import", alias = "i")]
     Import(RepoImport),
 
+    /// Sign the advisories of the RustSec advisory database as your own reviews
+    #[structopt(name = "import-from-advisory-db")]
+    ImportAdvisoryDb(RepoImportAdvisoryDb),
+
     /*
     /// Export proofs
     #[structopt(name = "export")]
@@ -623,6 +1657,58 @@ pub enum Repo {
     /// Fetch proofs from external sources
     #[structopt(name = "fetch", alias = "f")]
     Fetch(RepoFetch),
+
+    /// Clone a public proof repository to inspect it, without trusting anyone
+    #[structopt(name = "clone-proofs")]
+    CloneProofs(RepoFetchUrl),
+
+    /// Re-verify the signatures of all the proofs of fetched proof repositories
+    #[structopt(name = "verify-integrity")]
+    VerifyIntegrity(RepoVerifyIntegrity),
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Db {
+    /// Summarize the proofs known locally (own and fetched)
+    #[structopt(name = "stats")]
+    Stats {
+        /// Output format
+        #[structopt(
+            long = "output-format",
+            default_value = "text",
+            raw(possible_values = r#"&["text", "json"]"#)
+        )]
+        output_format: TextOrJson,
+    },
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct ProofVerify {
+    /// File with proofs to check (standard input if not given)
+    #[structopt(parse(from_os_str))]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub struct ProofInspect {
+    /// File with proofs to inspect
+    #[structopt(parse(from_os_str))]
+    pub path: PathBuf,
+
+    /// Also print the signature, the public key and the exact signed content
+    #[structopt(long = "show-signature")]
+    pub show_signature: bool,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Proof {
+    /// Check that proofs are valid and signed by their authors, without importing them
+    #[structopt(name = "verify", alias = "v")]
+    Verify(ProofVerify),
+
+    /// Print details of proofs in a file, eg. to check their signatures with other tools
+    #[structopt(name = "inspect", alias = "i")]
+    Inspect(ProofInspect),
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -645,6 +1731,21 @@ pub enum Command {
     /// Config
     #[structopt(name = "config", alias = "co")]
     Config(Config),
+
+    /// Proofs, outside of any proof repository
+    #[structopt(name = "proof", alias = "p")]
+    Proof(Proof),
+
+    /// Database of all the proofs known locally
+    #[structopt(name = "db")]
+    Db(Db),
+
+    /// Print names of the dependencies, for shell completions
+    #[structopt(
+        name = "complete-crates",
+        raw(setting = "structopt::clap::AppSettings::Hidden")
+    )]
+    CompleteCrates,
 }
 
 /// Cargo will pass the name of the `cargo-<tool>`
@@ -668,6 +1769,31 @@ Read user documentation at https://docs.rs/crate/cargo-crev
 pub struct Opts {
     #[structopt(subcommand)]
     pub command: MainCommand,
+
+    #[structopt(
+        long = "proof-dir",
+        value_name = "PATH",
+        parse(from_os_str),
+        raw(global = "true")
+    )]
+    /// Use a different crev root directory instead of `~/.config/crev`
+    pub proof_dir: Option<PathBuf>,
+
+    /// Log format: `text`, or `json` lines for log pipelines (filter with `RUST_LOG`)
+    #[structopt(
+        long = "log-format",
+        default_value = "text",
+        raw(global = "true", possible_values = r#"&["text", "json"]"#)
+    )]
+    pub log_format: TextOrJson,
+
+    #[structopt(long = "no-color", raw(global = "true"))]
+    /// Disable colored output (also disabled if `NO_COLOR` is set)
+    pub no_color: bool,
+
+    #[structopt(long = "rebuild-index", raw(global = "true"))]
+    /// Read all proof files again, instead of using the index of the ones already read
+    pub rebuild_index: bool,
     //    #[structopt(flatten)]
     //    verbosity: Verbosity,
 }
@@ -19,7 +19,7 @@ use petgraph::graph::NodeIndex;
 use std::{
     collections::{hash_map::Entry, BTreeSet, HashMap, HashSet},
     env,
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
     str::{self, FromStr},
 };
@@ -33,7 +33,7 @@ struct Node {
     metadata: ManifestMetadata,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Graph {
     graph: petgraph::Graph<Node, Kind>,
     nodes: HashMap<PackageId, NodeIndex>,
@@ -72,34 +72,88 @@ impl Graph {
             .map(move |node_idx| self.graph.node_weight(node_idx).unwrap().id)
     }
 
-    pub fn get_recursive_dependencies_of(&self, root_pkg_id: PackageId) -> HashSet<PackageId> {
-        let mut pending = BTreeSet::new();
-        let mut processed = HashSet::new();
-
-        pending.insert(root_pkg_id);
-
-        while let Some(pkg_id) = pending.iter().next().cloned() {
-            pending.remove(&pkg_id);
+    /// Length of the longest dependency chain from a root (a package nothing
+    /// depends on) to each package
+    ///
+    /// Also returns the package each one was reached from, to follow that
+    /// chain back to the root.
+    pub fn get_depths(&self) -> HashMap<PackageId, (u64, Option<PackageId>)> {
+        let mut depths = HashMap::new();
+        let mut visiting = HashSet::new();
+        let pkg_ids: BTreeSet<_> = self.get_all_pkg_ids().collect();
+        for pkg_id in pkg_ids {
+            self.calculate_depth(pkg_id, &mut depths, &mut visiting);
+        }
+        depths
+    }
 
-            if processed.contains(&pkg_id) {
+    /// Depth of `pkg_id`, from the depths of the packages depending on it
+    ///
+    /// Dependencies closing a cycle (possible with dev-dependencies) are ignored.
+    fn calculate_depth(
+        &self,
+        pkg_id: PackageId,
+        depths: &mut HashMap<PackageId, (u64, Option<PackageId>)>,
+        visiting: &mut HashSet<PackageId>,
+    ) -> u64 {
+        if let Some((depth, _)) = depths.get(&pkg_id) {
+            return *depth;
+        }
+        visiting.insert(pkg_id);
+        let parents: BTreeSet<_> = self.get_reverse_dependencies_of(pkg_id).collect();
+        let mut deepest = (0, None);
+        for parent in parents {
+            if visiting.contains(&parent) {
                 continue;
-            } else {
-                processed.insert(pkg_id);
             }
+            let depth = self.calculate_depth(parent, depths, visiting) + 1;
+            if depth > deepest.0 {
+                deepest = (depth, Some(parent));
+            }
+        }
+        visiting.remove(&pkg_id);
+        depths.insert(pkg_id, deepest);
+        deepest.0
+    }
 
-            if let Some(node_idx) = self.nodes.get(&pkg_id) {
-                for node_idx in self
-                    .graph
-                    .neighbors_directed(*node_idx, petgraph::Direction::Outgoing)
-                {
-                    pending.insert(self.graph.node_weight(node_idx).unwrap().id);
+    /// All the dependencies of `root_pkg_id`, direct and indirect
+    ///
+    /// With `max_depth`, only up to that many levels below the direct
+    /// dependencies (`0` - direct dependencies only).
+    pub fn get_recursive_dependencies_of(
+        &self,
+        root_pkg_id: PackageId,
+        max_depth: Option<u64>,
+    ) -> HashSet<PackageId> {
+        let mut processed = HashSet::new();
+        let mut level = BTreeSet::new();
+        level.insert(root_pkg_id);
+        processed.insert(root_pkg_id);
+
+        // breadth-first, so each package is reached at its smallest depth
+        let mut depth = 0;
+        while !level.is_empty() && max_depth.map_or(true, |max| depth <= max) {
+            let mut next_level = BTreeSet::new();
+            for pkg_id in level {
+                if let Some(node_idx) = self.nodes.get(&pkg_id) {
+                    for node_idx in self
+                        .graph
+                        .neighbors_directed(*node_idx, petgraph::Direction::Outgoing)
+                    {
+                        let dep_pkg_id = self.graph.node_weight(node_idx).unwrap().id;
+                        if processed.insert(dep_pkg_id) {
+                            next_level.insert(dep_pkg_id);
+                        }
+                    }
+                } else {
+                    eprintln!(
+                        "No node for {} when checking recdeps for {}",
+                        pkg_id, root_pkg_id
+                    );
                 }
-            } else {
-                eprintln!(
-                    "No node for {} when checking recdeps for {}",
-                    pkg_id, root_pkg_id
-                );
             }
+            level = next_level;
+            depth += 1;
         }
 
         processed.remove(&root_pkg_id);
@@ -308,6 +362,55 @@ impl Repo {
         })
     }
 
+    /// Directory containing the workspace root `Cargo.toml`
+    pub fn workspace_dir(&self) -> &Path {
+        self.manifest_path.parent().unwrap()
+    }
+
+    /// Names and versions of all the packages in `Cargo.lock` at a given git ref
+    pub fn get_lock_packages_at_git_ref(&self, git_ref: &str) -> Result<HashSet<(String, String)>> {
+        let output = std::process::Command::new("git")
+            .arg("show")
+            .arg(format!("{}:./Cargo.lock", git_ref))
+            .current_dir(self.workspace_dir())
+            .output()?;
+        if !output.status.success() {
+            bail!(
+                "Can't read `Cargo.lock` at `{}`: {}",
+                git_ref,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let lock: toml::Value = toml::from_str(str::from_utf8(&output.stdout)?)?;
+
+        Ok(lock
+            .get("package")
+            .and_then(|p| p.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|pkg| {
+                Some((
+                    pkg.get("name")?.as_str()?.to_owned(),
+                    pkg.get("version")?.as_str()?.to_owned(),
+                ))
+            })
+            .collect())
+    }
+
+    /// Commit checked out in the workspace, if it's in a git repository
+    pub fn get_head_commit(&self) -> Option<String> {
+        let output = std::process::Command::new("git")
+            .arg("rev-parse")
+            .arg("HEAD")
+            .current_dir(self.workspace_dir())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+
     pub fn name(&self) -> std::borrow::Cow<'_, str> {
         self.manifest_path
             .parent()
@@ -350,7 +453,7 @@ impl Repo {
             &self.features_list,
             self.cargo_opts.all_features,
             self.cargo_opts.no_default_features,
-            self.cargo_opts.no_dev_dependencies,
+            self.cargo_opts.skip_dev_dependencies(),
         )?;
 
         let rustc = self.config.load_global_rustc(Some(&workspace))?;
@@ -434,7 +537,7 @@ impl Repo {
             &self.features_list,
             self.cargo_opts.all_features,
             self.cargo_opts.no_default_features,
-            self.cargo_opts.no_dev_dependencies,
+            self.cargo_opts.skip_dev_dependencies(),
         )?;
         let mut source = self.load_source()?;
 
@@ -475,7 +578,7 @@ impl Repo {
             &self.features_list,
             self.cargo_opts.all_features,
             self.cargo_opts.no_default_features,
-            self.cargo_opts.no_dev_dependencies,
+            self.cargo_opts.skip_dev_dependencies(),
         )?;
 
         for pkg_id in package_set.package_ids() {
@@ -515,7 +618,7 @@ impl Repo {
             &self.features_list,
             self.cargo_opts.all_features,
             self.cargo_opts.no_default_features,
-            self.cargo_opts.no_dev_dependencies,
+            self.cargo_opts.skip_dev_dependencies(),
         )?)
     }
 
@@ -1,10 +1,10 @@
 use crev_data::Rating;
 use crev_lib::{self, local::Local};
 use failure::format_err;
-use std::default::Default;
+use std::{default::Default, path::PathBuf};
 
 use crate::{
-    opts,
+    crates_io, opts,
     opts::{CargoOpts, CrateSelector},
     prelude::*,
 };
@@ -13,6 +13,62 @@ use crev_lib::TrustProofType;
 
 use crate::{repo::*, shared::*};
 
+/// Find the latest version of a crate trusted by the current Id,
+/// to be used as a base of a differential review of `version`
+fn find_latest_trusted_version_to_diff(
+    local: &Local,
+    name: &str,
+    version: &Version,
+) -> Result<Version> {
+    let db = local.load_db()?;
+    let trust_set = db.calculate_trust_set(
+        &local.get_current_userid()?,
+        &crev_lib::TrustDistanceParams::default(),
+    );
+    let trusted_version = db
+        .find_latest_trusted_version(
+            &trust_set,
+            PROJECT_SOURCE_CRATES_IO,
+            name,
+            &crev_lib::VerificationRequirements::default(),
+        )
+        .ok_or_else(|| format_err!("No trusted version of {} to diff from", name))?;
+
+    if &trusted_version == version {
+        bail!("{} v{} is already trusted", name, version);
+    }
+
+    Ok(trusted_version)
+}
+
+/// Template for the comment of a new review, with `{crate}` and `{version}` filled in
+///
+/// The project's `.crev/review-template.md` takes precedence over the file
+/// set by the `review-template` config key.
+fn load_review_template(
+    repo: &Repo,
+    local: &Local,
+    name: &str,
+    version: &Version,
+) -> Result<Option<String>> {
+    let project_template = repo.workspace_dir().join(PROJECT_REVIEW_TEMPLATE_FILE);
+    let path = if project_template.exists() {
+        project_template
+    } else if let Some(path) = local.load_user_config()?.review_template {
+        PathBuf::from(path)
+    } else {
+        return Ok(None);
+    };
+
+    let template = std::fs::read_to_string(&path)
+        .with_context(|_| format!("Can't read review template {}", path.display()))?;
+    Ok(Some(
+        template
+            .replace("{crate}", name)
+            .replace("{version}", &version.to_string()),
+    ))
+}
+
 /// Review a crate
 ///
 /// * `unrelated` - the crate might not actually be a dependency
@@ -23,7 +79,9 @@ pub fn create_review_proof(
     advise_common: Option<opts::AdviseCommon>,
     trust: TrustProofType,
     proof_create_opt: &opts::CommonProofCreate,
+    print_only: bool,
     diff_version: &Option<Option<Version>>,
+    diff_from_trusted: bool,
     skip_activity_check: bool,
     cargo_opts: CargoOpts,
 ) -> Result<()> {
@@ -37,6 +95,16 @@ pub fn create_review_proof(
     assert!(!crate_root.starts_with(std::env::current_dir()?));
     let local = Local::auto_open()?;
 
+    let diff_version = if diff_from_trusted {
+        Some(Some(find_latest_trusted_version_to_diff(
+            &local,
+            &pkg_id.name(),
+            effective_crate_version,
+        )?))
+    } else {
+        diff_version.clone()
+    };
+
     let diff_base_version = crate_review_activity_check(
         &local,
         &pkg_id.name(),
@@ -70,11 +138,20 @@ pub fn create_review_proof(
         None
     };
 
-    let id = local.read_current_unlocked_id(&crev_common::read_passphrase)?;
+    // nothing gets signed with `print_only`, so don't ask for the passphrase
+    let signer = if print_only {
+        None
+    } else {
+        Some(local.read_current_signer(&crev_common::read_passphrase)?)
+    };
+    let from = match signer {
+        Some(ref signer) => signer.as_pubid().to_owned(),
+        None => local.read_current_locked_id()?.to_pubid(),
+    };
 
     let db = local.load_db()?;
     let mut review = proof::review::PackageBuilder::default()
-        .from(id.id.to_owned())
+        .from(from.clone())
         .package(proof::PackageInfo {
             id: None,
             source: PROJECT_SOURCE_CRATES_IO.to_owned(),
@@ -98,7 +175,7 @@ pub fn create_review_proof(
         if let Some((prev_date, prev_review, prev_advisories, prev_issues, prev_comment)) =
             find_previous_review_data(
                 &db,
-                &id.id,
+                &from,
                 &crate_.name(),
                 effective_crate_version,
                 &diff_base_version,
@@ -115,6 +192,14 @@ pub fn create_review_proof(
             None
         };
 
+    if review.comment.is_empty() {
+        if let Some(template) =
+            load_review_template(&repo, &local, &crate_.name(), effective_crate_version)?
+        {
+            review.comment = template;
+        }
+    }
+
     if let Some(advise_common) = advise_common {
         let mut advisory: proof::review::package::Advisory = advise_common.affected.into();
         advisory.severity = advise_common.severity;
@@ -126,13 +211,28 @@ pub fn create_review_proof(
         review.issues.push(report);
         review.review.rating = Rating::Negative;
     }
+    if print_only {
+        print!("{}", proof::Content::from(review));
+        return Ok(());
+    }
+
     let review = crev_lib::util::edit_proof_content_iteractively(
         &review.into(),
         previous_date.as_ref(),
         diff_base_version.as_ref(),
     )?;
 
-    let proof = review.sign_by(&id)?;
+    let proof = signer
+        .expect("signer is loaded unless `print_only`")
+        .sign(&review)?;
+
+    // remember crate owners at the time of the review, to detect ownership changes
+    if let Ok(crates_io) = crates_io::Client::new(&local) {
+        if let Ok(owners) = crates_io.get_owners(&crate_.name()) {
+            let _ =
+                crates_io.record_reviewed_owners(&crate_.name(), effective_crate_version, &owners);
+        }
+    }
 
     let commit_msg = format!(
         "Add review for {crate} v{version}",
@@ -142,6 +242,57 @@ pub fn create_review_proof(
     maybe_store(&local, &proof, &commit_msg, proof_create_opt)
 }
 
+/// Withdraw an earlier positive review of a crate
+///
+/// The new review has no rating and supersedes the previous one, so the crate
+/// no longer counts as reviewed by the current Id. Unlike `--issue` or `--advisory`
+/// it doesn't report anything negative about the crate.
+pub fn withdraw_review(
+    crate_sel: &CrateSelector,
+    proof_create_opt: &opts::CommonProofCreate,
+    cargo_opts: CargoOpts,
+) -> Result<()> {
+    let repo = Repo::auto_open_cwd(cargo_opts.clone())?;
+    let pkg_id = repo.find_pkgid_by_crate_selector(crate_sel)?;
+    let local = Local::auto_open()?;
+    let db = local.load_db()?;
+    let id = local.get_current_userid()?;
+
+    match db.get_pkg_review(
+        PROJECT_SOURCE_CRATES_IO,
+        &pkg_id.name(),
+        pkg_id.version(),
+        &id,
+    ) {
+        Some(review) if !review.review.is_none() && review.review.rating > Rating::Neutral => {}
+        _ => bail!(
+            "No positive review of {} {} by the current Id to withdraw",
+            pkg_id.name(),
+            pkg_id.version()
+        ),
+    }
+
+    eprintln!(
+        "Withdrawing your review of {} {}. This is not a negative review: \
+         the crate just won't count as reviewed by you anymore.",
+        pkg_id.name(),
+        pkg_id.version()
+    );
+
+    create_review_proof(
+        crate_sel,
+        None,
+        None,
+        TrustProofType::Untrust,
+        proof_create_opt,
+        false,
+        &None,
+        false,
+        true,
+        cargo_opts,
+    )
+}
+
 pub fn find_previous_review_data(
     db: &crev_lib::ProofDB,
     id: &crev_data::PubId,
@@ -194,10 +345,9 @@ pub fn find_reviews(crate_: &opts::CrateSelector) -> Result<Vec<proof::review::P
         .collect())
 }
 
-pub fn list_reviews(crate_: &opts::CrateSelector) -> Result<()> {
-    for review in find_reviews(crate_)? {
-        println!("---\n{}", review);
-    }
-
-    Ok(())
+pub fn list_reviews(
+    crate_: &opts::CrateSelector,
+    output_format: opts::ReviewOutputFormat,
+) -> Result<()> {
+    print_package_reviews(find_reviews(crate_)?.iter(), output_format)
 }
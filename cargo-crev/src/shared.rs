@@ -1,14 +1,18 @@
 // Here are the structs and functions which still need to be sorted
 //
-use crate::{deps::scan, opts, opts::CrateSelector, prelude::*, repo::*};
+use crate::{crates_io, deps::scan, opts, opts::CrateSelector, prelude::*, repo::*};
 use crev_data::proof;
-use crev_lib::{self, local::Local, ProofStore, ReviewMode, TrustProofType};
+use crev_lib::{
+    self,
+    local::{Local, VerifyConfig},
+    ProofStore, ReviewMode, TrustProofType,
+};
 use failure::format_err;
 use insideout::InsideOutIter;
 use resiter::FlatMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
     env,
     ffi::{OsStr, OsString},
     io,
@@ -32,6 +36,55 @@ pub const PROJECT_SOURCE_CRATES_IO: &str = "https://crates.io";
 /// The file added to crates containing vcs revision
 pub const VCS_INFO_JSON_FILE: &str = ".cargo_vcs_info.json";
 
+/// Project-local config, relative to the workspace root
+pub const PROJECT_CONFIG_FILE: &str = ".crev/config.toml";
+
+/// Crates of the project that don't require reviews, relative to the workspace root
+pub const PROJECT_IGNORE_FILE: &str = ".crev/ignore";
+
+/// Template for the comment of new reviews, relative to the workspace root
+pub const PROJECT_REVIEW_TEMPLATE_FILE: &str = ".crev/review-template.md";
+
+/// Data from `.crev/config.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    verify: VerifyConfig,
+}
+
+impl ProjectConfig {
+    fn read_from_workspace_dir(dir: &Path) -> Result<Self> {
+        let path = dir.join(PROJECT_CONFIG_FILE);
+
+        if path.exists() {
+            let txt = crev_common::read_file_to_string(&path)?;
+            Ok(toml::from_str(&txt).with_context(|_| format!("Can't parse {}", path.display()))?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+}
+
+/// Defaults for `crate verify` flags not given on the command line
+///
+/// The project's `.crev/config.toml` takes precedence over the
+/// `verify` section of the user config.
+pub fn load_verify_config(cargo_opts: &opts::CargoOpts) -> Result<VerifyConfig> {
+    let repo = Repo::auto_open_cwd(cargo_opts.clone())?;
+    let project = ProjectConfig::read_from_workspace_dir(repo.workspace_dir())?;
+    let local = Local::auto_create_or_open()?;
+    let user = local.load_user_config()?;
+    Ok(project.verify.or(user.verify))
+}
+
+/// Command to run after `crate verify`, when not given on the command line
+///
+/// Only ever taken from the user config, never from the project's.
+pub fn load_post_verify_hook() -> Result<Option<String>> {
+    let local = Local::auto_create_or_open()?;
+    Ok(local.load_user_config()?.post_verify_hook)
+}
+
 /// Data from `.cargo_vcs_info.json`
 #[derive(Debug, Clone, Deserialize)]
 pub struct VcsInfoJson {
@@ -109,6 +162,15 @@ pub fn exec_into(mut command: process::Command) -> Result<()> {
 /// Set some `envs` to help other commands work
 /// from inside such a "review-shell".
 pub fn goto_crate_src(selector: &opts::CrateSelector) -> Result<()> {
+    run_goto_shell(selector, true)
+}
+
+/// Like `goto_crate_src`, but return once the shell exits
+pub fn goto_crate_src_and_wait(selector: &opts::CrateSelector) -> Result<()> {
+    run_goto_shell(selector, false)
+}
+
+fn run_goto_shell(selector: &opts::CrateSelector, exec: bool) -> Result<()> {
     if env::var(GOTO_ORIGINAL_DIR_ENV).is_ok() {
         bail!("You're already in a `cargo crev goto` shell");
     };
@@ -130,9 +192,24 @@ pub fn goto_crate_src(selector: &opts::CrateSelector) -> Result<()> {
     let shell = env::var_os("SHELL").ok_or_else(|| format_err!("$SHELL not set"))?;
     let cwd = env::current_dir()?;
 
+    // on Unix the shell keeps our pid, so it can be checked later if the session is still alive
+    let session = GotoSession {
+        pid: process::id(),
+        name: crate_name.to_string(),
+        version: crate_version.clone(),
+        path: crate_dir.to_owned(),
+        original_dir: cwd.clone(),
+    };
+    let session_path = goto_sessions_dir(&local).join(format!("{}.yaml", session.pid));
+    crev_common::save_to_yaml_file(&session_path, &session)?;
+
     eprintln!("Opening shell in: {}", crate_dir.display());
-    eprintln!("Use `exit` or Ctrl-D to return to the original project.",);
-    eprintln!("Use `review` and `flag` without any arguments to review this crate.");
+    if exec {
+        eprintln!("Use `exit` or Ctrl-D to return to the original project.",);
+        eprintln!("Use `review` and `flag` without any arguments to review this crate.");
+    } else {
+        eprintln!("Use `exit` or Ctrl-D when done reading the code, to write the review.",);
+    }
     let mut command = process::Command::new(shell);
     command
         .current_dir(crate_dir)
@@ -141,7 +218,102 @@ pub fn goto_crate_src(selector: &opts::CrateSelector) -> Result<()> {
         .env(GOTO_CRATE_NAME_ENV, crate_name.to_string())
         .env(GOTO_CRATE_VERSION_ENV, &crate_version.to_string());
 
-    exec_into(command)
+    let res = if exec {
+        exec_into(command)
+    } else {
+        match command.status() {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    };
+    let _ = std::fs::remove_file(&session_path);
+    res
+}
+
+/// A `goto` shell, as recorded when it was started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GotoSession {
+    pub pid: u32,
+    pub name: String,
+    pub version: Version,
+    pub path: PathBuf,
+    pub original_dir: PathBuf,
+}
+
+impl GotoSession {
+    /// Is the shell of this session still running
+    #[cfg(target_family = "unix")]
+    pub fn is_alive(&self) -> bool {
+        self.path.exists()
+            && process::Command::new("kill")
+                .arg("-0")
+                .arg(self.pid.to_string())
+                .stderr(process::Stdio::null())
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false)
+    }
+
+    /// Is the shell of this session still running
+    ///
+    /// The session is removed when the shell returns, so we can only
+    /// check if the crate is still there.
+    #[cfg(target_family = "windows")]
+    pub fn is_alive(&self) -> bool {
+        self.path.exists()
+    }
+}
+
+fn goto_sessions_dir(local: &Local) -> PathBuf {
+    local.get_root_cache_dir().join("goto")
+}
+
+/// Read all recorded `goto` sessions, with the paths of their markers
+pub fn read_goto_sessions(local: &Local) -> Result<Vec<(PathBuf, GotoSession)>> {
+    let dir = goto_sessions_dir(local);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut sessions = vec![];
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new("yaml")) {
+            continue;
+        }
+        match crev_common::read_from_yaml_file(&path) {
+            Ok(session) => sessions.push((path, session)),
+            Err(e) => eprintln!("Can't read {}: {}", path.display(), e),
+        }
+    }
+    sessions.sort_by_key(|(_, session): &(PathBuf, GotoSession)| session.pid);
+    Ok(sessions)
+}
+
+/// List `goto` sessions, optionally removing markers of the ones that are gone
+pub fn list_goto_sessions(clean_stale: bool) -> Result<()> {
+    let local = Local::auto_create_or_open()?;
+    for (marker_path, session) in read_goto_sessions(&local)? {
+        let alive = session.is_alive();
+        if !alive && clean_stale {
+            std::fs::remove_file(&marker_path)?;
+            eprintln!(
+                "Removed stale session {} {} {}",
+                session.pid, session.name, session.version
+            );
+            continue;
+        }
+        println!(
+            "{:>7} {} {} {}{}",
+            session.pid,
+            session.name,
+            session.version,
+            session.path.display(),
+            if alive { "" } else { " (stale)" }
+        );
+    }
+    Ok(())
 }
 
 pub fn ensure_known_owners_list_exists(local: &crev_lib::Local) -> Result<()> {
@@ -162,12 +334,29 @@ pub fn read_known_owners_list() -> Result<HashSet<String>> {
     } else {
         include_str!("known_cargo_owners_defaults.txt").to_string()
     };
-    Ok(content
+    Ok(parse_owners_list(&content))
+}
+
+fn parse_owners_list(content: &str) -> HashSet<String> {
+    content
         .lines()
         .map(str::trim)
         .filter(|s| !s.starts_with('#'))
         .map(ToString::to_string)
-        .collect())
+        .collect()
+}
+
+/// Known owners, plus the ones listed in `owners_from` (eg. a list shared by a team)
+///
+/// A missing personal list is treated as empty, but the extra list must be readable.
+pub fn read_known_owners_with(owners_from: Option<&Path>) -> Result<HashSet<String>> {
+    let mut owners = read_known_owners_list().unwrap_or_else(|_| HashSet::new());
+    if let Some(path) = owners_from {
+        let content = crev_common::read_file_to_string(path)
+            .with_context(|_| format!("Can't read owners list {}", path.display()))?;
+        owners.extend(parse_owners_list(&content));
+    }
+    Ok(owners)
 }
 
 pub fn edit_known_owners_list() -> Result<()> {
@@ -178,17 +367,75 @@ pub fn edit_known_owners_list() -> Result<()> {
     Ok(())
 }
 
-pub fn clean_all_unclean_crates() -> Result<()> {
+/// Replace the `from` login with `to` in the known owners list, returning how many lines changed
+fn rename_owner_in_list(content: &str, from: &str, to: &str) -> (String, usize) {
+    let mut count = 0;
+    let mut renamed = String::with_capacity(content.len());
+    for line in content.lines() {
+        if !line.starts_with('#') && line.trim() == from {
+            renamed.push_str(&line.replace(from, to));
+            count += 1;
+        } else {
+            renamed.push_str(line);
+        }
+        renamed.push('\n');
+    }
+    (renamed, count)
+}
+
+pub fn rename_known_owner(from: &str, to: &str) -> Result<()> {
+    let local = Local::auto_create_or_open()?;
+    let path = local.get_proofs_dir_path()?.join(KNOWN_CARGO_OWNERS_FILE);
+    ensure_known_owners_list_exists(&local)?;
+    let content = crev_common::read_file_to_string(&path)?;
+    if parse_owners_list(&content).contains(to) {
+        bail!("`{}` is already a known owner", to);
+    }
+    let (renamed, count) = rename_owner_in_list(&content, from, to);
+    if count == 0 {
+        bail!(
+            "`{}` is not in the known owners list {}",
+            from,
+            path.display()
+        );
+    }
+    crev_common::store_str_to_file(&path, &renamed)?;
+    eprintln!(
+        "Renamed `{}` to `{}` in {} ({} reference{} updated)",
+        from,
+        to,
+        path.display(),
+        count,
+        if count == 1 { "" } else { "s" }
+    );
+    // so crates reviewed under the old login don't look like they changed owners
+    let crates_count = crates_io::Client::new(&local)?.rename_reviewed_owner(from, to)?;
+    if crates_count > 0 {
+        eprintln!(
+            "Renamed `{}` to `{}` in the recorded owners of {} reviewed crate{}",
+            from,
+            to,
+            crates_count,
+            if crates_count == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+pub fn clean_all_unclean_crates(dry_run: bool) -> Result<()> {
     let scanner = scan::Scanner::new(&opts::CrateVerify::default())?;
     let events = scanner.run();
 
     for stats in events.into_iter() {
         if stats.is_digest_unclean() {
-            clean_crate(&CrateSelector {
-                name: Some(stats.info.id.name().to_string()),
-                version: Some(stats.info.id.version().to_owned()),
-                unrelated: false,
-            })?;
+            clean_crate(
+                &CrateSelector {
+                    name: Some(stats.info.id.name().to_string()),
+                    version: Some(stats.info.id.version().to_owned()),
+                    unrelated: false,
+                },
+                dry_run,
+            )?;
         }
     }
 
@@ -196,18 +443,43 @@ pub fn clean_all_unclean_crates() -> Result<()> {
 }
 
 /// Wipe the crate source, then re-download it
-pub fn clean_crate(selector: &CrateSelector) -> Result<()> {
+///
+/// With `dry_run`, only print the directory that would be wiped.
+pub fn clean_crate(selector: &CrateSelector, dry_run: bool) -> Result<()> {
     let repo = Repo::auto_open_cwd_default()?;
     let crate_id = repo.find_pkgid_by_crate_selector(selector)?;
     let crate_ = repo.get_crate(&crate_id)?;
-    let crate_root = crate_.root();
+    if dry_run {
+        println!(
+            "{:>10} KiB {}",
+            (dir_size(crate_.root()) + 1023) / 1024,
+            crate_.root().display()
+        );
+        return Ok(());
+    }
+    wipe_crate_source(crate_.root())?;
+    let _crate_ = repo.get_crate(&crate_id)?;
+    Ok(())
+}
 
+/// Total size of the files in `dir`, skipping the ones that can't be read
+fn dir_size(dir: &Path) -> u64 {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Remove the extracted source of a crate, so it can be downloaded again
+pub fn wipe_crate_source(crate_root: &Path) -> Result<()> {
     assert!(!crate_root.starts_with(std::env::current_dir()?));
 
     if crate_root.is_dir() {
         std::fs::remove_dir_all(&crate_root)?;
     }
-    let _crate_ = repo.get_crate(&crate_id)?;
     Ok(())
 }
 
@@ -484,22 +756,50 @@ pub fn run_diff(args: &opts::Diff) -> Result<std::process::ExitStatus> {
         &crev_lib::ReviewActivity::new_diff(&src_version),
     )?;
 
-    use std::process::Command;
+    let diff_command = match args.diff_command {
+        Some(ref cmd) => Some(cmd.clone()),
+        None if !args.stat => local.load_user_config()?.diff_cmd,
+        None => None,
+    };
+    if let Some(cmd) = diff_command {
+        if !args.args.is_empty() {
+            bail!("Arguments to `diff` can't be used with a diff command; add them to it instead");
+        }
+        return Ok(crev_common::run_with_shell_cmd_args(
+            cmd.into(),
+            &[src_crate.root(), dst_crate.root()],
+        )?);
+    }
 
-    let diff = |exe| {
-        let mut command = Command::new(exe);
+    let diff = |exe: &OsStr| {
+        let mut command = process::Command::new(exe);
+        command.arg("-r").arg("-N");
+        if args.stat {
+            command.arg("-u");
+        }
         command
-            .arg("-r")
-            .arg("-N")
             .arg(src_crate.root())
             .arg(dst_crate.root())
             .args(&args.args);
         command
     };
 
+    if args.stat {
+        let output = run_diff_command(diff, process::Command::output)?;
+        print_diff_stat(&String::from_utf8_lossy(&output.stdout), dst_crate.root());
+        Ok(output.status)
+    } else {
+        run_diff_command(diff, process::Command::status)
+    }
+}
+
+fn run_diff_command<T>(
+    diff: impl Fn(&OsStr) -> process::Command,
+    run: impl Fn(&mut process::Command) -> io::Result<T>,
+) -> Result<T> {
     let mut command = diff(OsStr::new("diff"));
 
-    match command.status() {
+    match run(&mut command) {
         Err(ref err) if err.kind() == io::ErrorKind::NotFound && cfg!(windows) => {
             // On Windows, diff is likely available but *not* in %PATH.  Specifically, the git installer warns that
             // adding *nix tools to %PATH% will change the behavior of some built in windows commands like "find", and
@@ -511,29 +811,238 @@ pub fn run_diff(args: &opts::Diff) -> Result<std::process::ExitStatus> {
             diff_exe.push(r"Git\usr\bin\diff.exe");
 
             let mut command = diff(diff_exe.as_os_str());
-            command
-                .status()
-                .or_else(|err| panic!("Failed to execute {:?}\n{:?}", command, err))
+            Ok(run(&mut command)
+                .unwrap_or_else(|err| panic!("Failed to execute {:?}\n{:?}", command, err)))
         }
         Err(ref err) => panic!("Failed to execute {:?}\n{:?}", command, err),
-        Ok(status) => Ok(status),
+        Ok(res) => Ok(res),
+    }
+}
+
+/// Changes to a single file, in a `crate diff --stat` summary
+#[derive(Default, Debug, PartialEq)]
+struct DiffFileStat {
+    path: String,
+    insertions: u64,
+    deletions: u64,
+    touches_unsafe: bool,
+}
+
+/// Changes to each file in an unified `diff -r` output
+fn parse_diff_stat(diff_output: &str, dst_root: &Path) -> Vec<DiffFileStat> {
+    let dst_root = dst_root.to_string_lossy();
+    let relative_path = |path: &str| {
+        path.trim_start_matches(dst_root.as_ref())
+            .trim_start_matches(&['/', '\\'][..])
+            .to_owned()
+    };
+
+    let mut files: Vec<DiffFileStat> = vec![];
+    // `---`/`+++` are file headers only between `diff` and the first hunk;
+    // in a hunk they are removed `--` and added `++` lines
+    let mut in_header = false;
+    for line in diff_output.lines() {
+        if line.starts_with("diff ") {
+            files.push(DiffFileStat::default());
+            in_header = true;
+        } else if line.starts_with("@@") {
+            in_header = false;
+        } else if in_header && line.starts_with("+++ ") {
+            if let Some(file) = files.last_mut() {
+                let path = line.trim_start_matches("+++ ").split('\t').next();
+                file.path = relative_path(path.unwrap_or(""));
+            }
+        } else if in_header && line.starts_with("--- ") {
+            // source file header
+        } else if line.starts_with("Binary files ") {
+            let path = line
+                .trim_end_matches(" differ")
+                .rsplit(" and ")
+                .next()
+                .unwrap_or("");
+            files.push(DiffFileStat {
+                path: relative_path(path),
+                ..DiffFileStat::default()
+            });
+        } else if let Some(file) = files.last_mut() {
+            let changed = if line.starts_with('+') {
+                file.insertions += 1;
+                true
+            } else if line.starts_with('-') {
+                file.deletions += 1;
+                true
+            } else {
+                false
+            };
+            if changed && file.path.ends_with(".rs") && line.contains("unsafe") {
+                file.touches_unsafe = true;
+            }
+        }
     }
+    files
 }
 
-pub fn show_dir(sel: &opts::CrateSelector) -> Result<()> {
+/// Summarize an unified `diff -r` output, similar to `git diff --stat`
+fn print_diff_stat(diff_output: &str, dst_root: &Path) {
+    let files = parse_diff_stat(diff_output, dst_root);
+
+    let path_width = files.iter().map(|f| f.path.len()).max().unwrap_or(0);
+    let max_changes = files
+        .iter()
+        .map(|f| f.insertions + f.deletions)
+        .max()
+        .unwrap_or(0);
+    // don't let the `+++---` graph grow too wide
+    let scale = |n: u64| {
+        if max_changes > 40 && n > 0 {
+            std::cmp::max(1, n * 40 / max_changes) as usize
+        } else {
+            n as usize
+        }
+    };
+
+    for file in &files {
+        println!(
+            " {:width$} | {:5} {}{}",
+            file.path,
+            file.insertions + file.deletions,
+            "+".repeat(scale(file.insertions)),
+            "-".repeat(scale(file.deletions)),
+            width = path_width
+        );
+    }
+    let insertions: u64 = files.iter().map(|f| f.insertions).sum();
+    let deletions: u64 = files.iter().map(|f| f.deletions).sum();
+    let plural = |n: u64| if n == 1 { "" } else { "s" };
+    println!(
+        " {} file{} changed, {} insertion{}(+), {} deletion{}(-)",
+        files.len(),
+        plural(files.len() as u64),
+        insertions,
+        plural(insertions),
+        deletions,
+        plural(deletions),
+    );
+
+    for file in &files {
+        if file.path.rsplit('/').next() == Some("build.rs") {
+            println!("Build script changed: {}", file.path);
+        }
+    }
+    for file in &files {
+        if file.touches_unsafe {
+            println!("`unsafe` code changed: {}", file.path);
+        }
+    }
+}
+
+pub fn show_dir(sel: &opts::CrateSelector, digest: bool) -> Result<()> {
     let repo = Repo::auto_open_cwd_default()?;
 
     let _ = sel.ensure_name_given()?;
     let crate_id = repo.find_pkgid_by_crate_selector(sel)?;
     let crate_ = repo.get_crate(&crate_id)?;
-    println!("{}", crate_.root().display());
+    if digest {
+        // same as `crate verify` does, so it can be compared with reviews
+        let digest = crev_lib::get_dir_digest(crate_.root(), &cargo_min_ignore_list())?;
+        println!("{}", digest);
+    } else {
+        println!("{}", crate_.root().display());
+    }
+
+    Ok(())
+}
+
+/// Print package reviews, eg. found by `repo query`
+pub fn print_package_reviews<'a>(
+    reviews: impl Iterator<Item = &'a proof::review::Package>,
+    output_format: opts::ReviewOutputFormat,
+) -> Result<()> {
+    match output_format {
+        opts::ReviewOutputFormat::Text => {
+            for review in reviews {
+                println!("---\n{}", review);
+            }
+        }
+        opts::ReviewOutputFormat::Yaml => {
+            for review in reviews {
+                // every document starts with its own `---`
+                println!("{}", serde_yaml::to_string(review)?);
+            }
+        }
+        opts::ReviewOutputFormat::Json => {
+            let reviews: Vec<_> = reviews.collect();
+            println!("{}", serde_json::to_string_pretty(&reviews)?);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn list_advisories(
+    crate_: &opts::CrateSelector,
+    output_format: opts::ReviewOutputFormat,
+) -> Result<()> {
+    print_package_reviews(find_advisories(crate_)?.iter(), output_format)
+}
+
+/// Print a summary of the local proof database
+pub fn print_db_stats(output_format: opts::TextOrJson) -> Result<()> {
+    let local = crev_lib::Local::auto_open()?;
+    let stats = local.load_db()?.stats();
+
+    match output_format {
+        opts::TextOrJson::Json => {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        }
+        opts::TextOrJson::Text => {
+            println!("Ids:                     {}", stats.ids);
+            println!("Proof repositories:      {}", stats.repos);
+            println!("Package reviews:         {}", stats.package_reviews);
+            println!("Trust proofs:            {}", stats.trust_proofs);
+            println!("Advisories:              {}", stats.advisories);
+            println!("Issues:                  {}", stats.issues);
+            println!("Reviewed crates:         {}", stats.reviewed_crates);
+            println!("Reviewed crate versions: {}", stats.reviewed_crate_versions);
+        }
+    }
 
     Ok(())
 }
 
-pub fn list_advisories(crate_: &opts::CrateSelector) -> Result<()> {
-    for review in find_advisories(crate_)? {
-        println!("---\n{}", review);
+/// Print the proof repositories in the local cache
+pub fn print_fetched_repos(output_format: opts::TextOrJson) -> Result<()> {
+    let local = crev_lib::Local::auto_create_or_open()?;
+    let repos = local.list_fetched_repos()?;
+
+    match output_format {
+        opts::TextOrJson::Json => {
+            let repos: Vec<_> = repos
+                .iter()
+                .map(|repo| {
+                    serde_json::json!({
+                        "url": repo.url,
+                        "path": repo.path,
+                        "ids": repo.author_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                        "proofs": repo.proof_count,
+                        "last-commit": repo.last_commit.map(|date| date.to_rfc3339()),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&repos)?);
+        }
+        opts::TextOrJson::Text => {
+            for repo in &repos {
+                println!("{}", repo.url);
+                for id in &repo.author_ids {
+                    println!("  id:          {}", id);
+                }
+                println!("  proofs:      {}", repo.proof_count);
+                if let Some(last_commit) = repo.last_commit {
+                    println!("  last commit: {}", last_commit.format("%Y-%m-%d %H:%M"));
+                }
+            }
+        }
     }
 
     Ok(())
@@ -547,17 +1056,16 @@ pub fn list_issues(args: &opts::RepoQueryIssue) -> Result<()> {
     let db = local.load_db()?;
     let trust_set = db.calculate_trust_set(&current_id, &trust_distance_params);
 
-    for review in db.get_pkg_reviews_with_issues_for(
-        PROJECT_SOURCE_CRATES_IO,
-        args.crate_.name.as_ref().map(String::as_str),
-        args.crate_.version.as_ref(),
-        &trust_set,
-        args.trust_level.into(),
-    ) {
-        println!("---\n{}", review);
-    }
-
-    Ok(())
+    print_package_reviews(
+        db.get_pkg_reviews_with_issues_for(
+            PROJECT_SOURCE_CRATES_IO,
+            args.crate_.name.as_ref().map(String::as_str),
+            args.crate_.version.as_ref(),
+            &trust_set,
+            args.trust_level.into(),
+        ),
+        args.output_format,
+    )
 }
 
 /// Are we executing from a shell started by `cargo crev goto`?
@@ -601,18 +1109,57 @@ where
     Ok(())
 }
 
+/// Like `handle_goto_mode_command`, but calls `f` for every crate if many were given
+pub fn handle_review_command<F>(args: &opts::CrateReview, mut f: F) -> Result<()>
+where
+    F: FnMut(&CrateSelector) -> Result<()>,
+{
+    if let Some(common) = args.single_crate()? {
+        return handle_goto_mode_command(&common, f);
+    }
+    if are_we_called_from_goto_shell().is_some() {
+        bail!("In `crev goto` mode no arguments can be given");
+    }
+
+    let selectors = args.crate_selectors()?;
+    for sel in &selectors {
+        f(sel)?;
+    }
+    if !args.print_only {
+        eprintln!("Created {} proofs", selectors.len());
+    }
+    Ok(())
+}
+
 pub fn create_trust_proof(
     ids: Vec<String>,
     trust_or_distrust: TrustProofType,
+    level: Option<crev_data::Level>,
+    comment: Option<String>,
+    dry_run_params: Option<opts::TrustDistanceParams>,
     proof_create_opt: &opts::CommonProofCreate,
 ) -> Result<()> {
     let local = Local::auto_open()?;
 
-    let own_id = local.read_current_unlocked_id(&crev_common::read_passphrase)?;
+    let trust_level = level
+        .map(crev_data::TrustLevel::from)
+        .unwrap_or_else(|| trust_or_distrust.to_trust_level());
+
+    if let Some(trust_params) = dry_run_params {
+        // nothing gets signed, so no need to unlock the Id, nor to ask for a comment
+        let pub_id = local.read_current_locked_id()?.to_pubid();
+        match local.build_trust_proof(&pub_id, ids, trust_level, Some(String::new()))? {
+            proof::Content::Trust(trust) => {
+                return print_trust_set_changes(&local, &trust, &trust_params.into())
+            }
+            _ => unreachable!("build_trust_proof builds trust proofs"),
+        }
+    }
 
-    let trust = local.build_trust_proof(own_id.as_pubid(), ids.clone(), trust_or_distrust)?;
+    let signer = local.read_current_signer(&crev_common::read_passphrase)?;
+    let trust = local.build_trust_proof(signer.as_pubid(), ids.clone(), trust_level, comment)?;
 
-    let proof = trust.sign_by(&own_id)?;
+    let proof = signer.sign(&trust)?;
     let commit_msg = format!(
         "Add {t_or_d} for {ids}",
         t_or_d = trust_or_distrust,
@@ -624,6 +1171,174 @@ pub fn create_trust_proof(
     Ok(())
 }
 
+/// Print which Ids would become trusted, distrusted or change trust level
+/// if `trust` was added to the proofs of `local`
+fn print_trust_set_changes(
+    local: &Local,
+    trust: &proof::Trust,
+    trust_params: &crev_lib::TrustDistanceParams,
+) -> Result<()> {
+    let own_id = local.get_current_userid()?;
+    let mut db = local.load_db()?;
+    let before = db.calculate_trust_set(&own_id, trust_params);
+    db.import_unsigned_trust(trust);
+    let after = db.calculate_trust_set(&own_id, trust_params);
+
+    let ids: BTreeSet<_> = before.trusted_ids().chain(after.trusted_ids()).collect();
+    let mut changed = 0;
+    for id in ids {
+        let level_before = before.get_effective_trust_level_opt(id);
+        let level_after = after.get_effective_trust_level_opt(id);
+        let change = match (level_before, level_after) {
+            (None, Some(level)) => format!("+ {:6}", level),
+            (Some(level), None) => format!("- {:6}", level),
+            (Some(from), Some(to)) if from != to => format!("~ {:6} -> {:6}", from, to),
+            _ => continue,
+        };
+        changed += 1;
+        println!(
+            "{} {} {}",
+            change,
+            id,
+            db.lookup_url(id).map(|url| url.url.as_str()).unwrap_or("")
+        );
+    }
+    eprintln!("{} trusted Id(s) would change; nothing was saved", changed);
+
+    Ok(())
+}
+
+/// Remove own trust proofs for an Id from the local proof repository
+///
+/// Meant for fixing mistakes before they are published. Proofs that are
+/// already upstream get removed too, but with a warning.
+pub fn remove_trust_proofs(id_str: &str) -> Result<()> {
+    let local = Local::auto_open()?;
+    let id = crev_data::Id::crevid_from_str(id_str)?;
+
+    let removed = local.remove_own_trust_proofs_for(&id)?;
+    if removed.is_empty() {
+        bail!("No trust proofs of yours found for {}", id);
+    }
+
+    local
+        .proof_dir_commit(&format!("Remove trust proof for {}", id))
+        .with_context(|_| format_err!("Could not not automatically commit"))?;
+    eprintln!("Removed {} trust proof(s) for {}", removed.len(), id);
+
+    if removed.iter().any(|&(_, published)| published) {
+        let mut term = crate::term::Term::new();
+        term.eprint(
+            format_args!(
+                "WARNING: some removed proofs were already published, and others may have fetched them.\n\
+                 Consider `cargo crev id untrust` or `cargo crev id distrust` instead.\n"
+            ),
+            ::term::color::RED,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Commit message for proofs not yet committed on `repo publish`
+///
+/// Uses `--message`, then `publish-message` from the user config,
+/// and falls back to a generic message.
+pub fn get_publish_commit_message(local: &Local, args: &opts::RepoPublish) -> Result<String> {
+    let mut msg = match args.message {
+        Some(ref msg) => msg.clone(),
+        None => local
+            .load_user_config()?
+            .publish_message
+            .unwrap_or_else(|| "auto-commit on `crev publish`".into()),
+    };
+
+    if args.with_crate_names {
+        let mut names: Vec<_> = local
+            .get_uncommitted_proofs()?
+            .into_iter()
+            .filter_map(|proof| match proof.content {
+                proof::Content::Package(review) => Some(format!(
+                    "{} {}",
+                    review.package.name, review.package.version
+                )),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        if !names.is_empty() {
+            msg = format!("{}\n\nReviews of:\n{}", msg, names.join("\n"));
+        }
+    }
+
+    Ok(msg)
+}
+
+/// Re-sign all proofs of an old own Id with the current Id
+///
+/// Also creates a trust proof from the old Id for the new one,
+/// linking the two.
+pub fn migrate_id_proofs(args: &opts::IdMigrate) -> Result<()> {
+    let local = Local::auto_open()?;
+    let from_id = crev_data::Id::crevid_from_str(&args.from)?;
+    let to_id = local.get_current_userid()?;
+    if let Some(ref to) = args.to {
+        if crev_data::Id::crevid_from_str(to)? != to_id {
+            bail!("Switch to {} first with `cargo crev id switch`", to);
+        }
+    }
+    if from_id == to_id {
+        bail!("Can't migrate proofs of the current Id to itself");
+    }
+
+    let from_locked_id = local.read_locked_id(&from_id)?;
+    let proofs = local.get_own_proofs_of(&from_locked_id.to_pubid())?;
+
+    if args.dry_run {
+        println!(
+            "{} proofs would be migrated from {} to {}",
+            proofs.len(),
+            from_id,
+            to_id
+        );
+        return Ok(());
+    }
+
+    eprintln!("Unlock the old Id ({}):", from_id);
+    let from_own_id = from_locked_id.to_unlocked(&crev_common::read_passphrase()?)?;
+    eprintln!("Unlock the current Id ({}):", to_id);
+    let signer = local.read_current_signer(&crev_common::read_passphrase)?;
+
+    let mut no_commit_opt = args.common_proof_create.clone();
+    no_commit_opt.no_commit = true;
+    for proof in &proofs {
+        let mut content = proof.content.clone();
+        content.set_author(signer.as_pubid());
+        let proof = signer.sign(&content)?;
+        maybe_store(&local, &proof, "", &no_commit_opt)?;
+    }
+
+    let link = from_own_id
+        .as_pubid()
+        .create_trust_proof(vec![signer.as_pubid()], crev_data::TrustLevel::High)?;
+    let link = link.sign_by(&from_own_id)?;
+    maybe_store(
+        &local,
+        &link,
+        &format!("Migrate {} proofs from {}", proofs.len(), from_id),
+        &args.common_proof_create,
+    )?;
+
+    eprintln!(
+        "Migrated {} proofs from {} to {}",
+        proofs.len(),
+        from_id,
+        to_id
+    );
+    Ok(())
+}
+
 pub fn is_file_with_ext(entry: &walkdir::DirEntry, file_ext: &str) -> bool {
     if !entry.file_type().is_file() {
         return false;
@@ -693,6 +1408,144 @@ pub fn is_digest_clean(
         || !at_least_one
 }
 
+/// Check proofs from a file (or stdin), without importing them
+pub fn verify_proofs(path: Option<&Path>) -> Result<CommandExitStatus> {
+    let proofs = match path {
+        Some(path) => proof::Proof::parse_each(io::BufReader::new(std::fs::File::open(path)?))?,
+        None => proof::Proof::parse_each(io::stdin())?,
+    };
+
+    let mut nb_invalid = 0;
+    for (i, proof) in proofs.iter().enumerate() {
+        let proof = match proof {
+            Ok(proof) => proof,
+            Err(e) => {
+                nb_invalid += 1;
+                println!("{:>4} invalid: can't parse: {}", i + 1, e);
+                continue;
+            }
+        };
+        let title = proof.content.draft_title();
+        let author = proof.content.author_id();
+        match proof.verify() {
+            Ok(()) => println!("{:>4} ok: {} by {}", i + 1, title, author),
+            Err(e) => {
+                nb_invalid += 1;
+                println!("{:>4} invalid: {} by {}: {}", i + 1, title, author, e);
+            }
+        }
+    }
+
+    eprintln!(
+        "{} proof{} checked, {} invalid",
+        proofs.len(),
+        if proofs.len() == 1 { "" } else { "s" },
+        nb_invalid
+    );
+    Ok(if nb_invalid == 0 {
+        CommandExitStatus::Success
+    } else {
+        CommandExitStatus::VerificationFailed
+    })
+}
+
+/// Print details of each proof in a file
+///
+/// With `show_signature`, also what's needed to check the signature independently:
+/// an ed25519 signature of the signed content, exactly as printed, by the public key.
+pub fn inspect_proofs(path: &Path, show_signature: bool) -> Result<CommandExitStatus> {
+    let proofs = proof::Proof::parse_each(io::BufReader::new(std::fs::File::open(path)?))?;
+
+    let mut nb_invalid = 0;
+    for (i, proof) in proofs.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let proof = match proof {
+            Ok(proof) => proof,
+            Err(e) => {
+                nb_invalid += 1;
+                println!("{:>4} can't parse: {}", i + 1, e);
+                continue;
+            }
+        };
+        let author = proof.content.author_id();
+        println!("{:>4} {}", i + 1, proof.content.draft_title());
+        println!(
+            "     author:    {} {}",
+            author,
+            proof.content.author_url().url
+        );
+        println!("     date:      {}", proof.content.date().to_rfc3339());
+        println!("     digest:    {}", hex::encode(&proof.digest));
+        match proof.verify() {
+            Ok(()) => println!("     signature: ok"),
+            Err(e) => {
+                nb_invalid += 1;
+                println!("     signature: invalid: {}", e);
+            }
+        }
+        if show_signature {
+            let crev_data::Id::Crev { id: ref public_key } = author;
+            println!("     algorithm: ed25519");
+            println!("     public key (hex): {}", hex::encode(public_key));
+            println!("     signature (base64): {}", proof.signature());
+            match crev_common::base64_decode(proof.signature()) {
+                Ok(signature) => println!("     signature (hex): {}", hex::encode(signature)),
+                Err(e) => println!("     signature (hex): can't decode: {}", e),
+            }
+            println!(
+                "     signed content ({} bytes, between the lines):",
+                proof.body.len()
+            );
+            println!("-----");
+            print!("{}", proof.body);
+            println!("-----");
+        }
+    }
+
+    Ok(if nb_invalid == 0 {
+        CommandExitStatus::Success
+    } else {
+        CommandExitStatus::VerificationFailed
+    })
+}
+
+/// Edit the most recent proof of the current Id, and sign it again
+///
+/// Published proofs are left alone: others might have fetched them already.
+pub fn edit_last_proof(no_commit: bool) -> Result<()> {
+    let local = crev_lib::Local::auto_open()?;
+    let (path, proof, published) = match local.find_last_own_proof()? {
+        Some(last) => last,
+        None => bail!("No proofs of the current Id found"),
+    };
+    if published {
+        bail!(
+            "The last proof ({}) was already published; create a new one instead",
+            proof.content.draft_title()
+        );
+    }
+
+    let signer = local.read_current_signer(&crev_common::read_passphrase)?;
+    let mut content = crev_lib::util::edit_proof_content_iteractively(
+        &proof.content,
+        Some(proof.content.date()),
+        None,
+    )?;
+    content.set_date(&crev_common::now());
+    let edited = signer.sign(&content)?;
+    local.replace_proof(&path, &proof, &edited)?;
+
+    if !no_commit {
+        local
+            .proof_dir_commit(&format!("Edit {}", content.draft_title()))
+            .with_context(|_| format_err!("Could not not automatically commit"))?;
+    }
+
+    Ok(())
+}
+
 pub fn maybe_store(
     local: &Local,
     proof: &crev_data::proof::Proof,
@@ -720,49 +1573,213 @@ pub fn maybe_store(
     Ok(())
 }
 
-pub fn lookup_crates(query: &str, count: usize) -> Result<()> {
+pub fn lookup_crates(query: &str, count: usize, sort: opts::SearchSort) -> Result<()> {
     struct CrateStats {
         name: String,
+        version: String,
+        description: String,
         downloads: u64,
         proof_count: usize,
+        trusted_reviewer_count: usize,
     }
 
     use crates_io_api::{ListOptions, Sort, SyncClient};
+    use opts::SearchSort;
 
     let local = crev_lib::Local::auto_create_or_open()?;
     let db = local.load_db()?;
+    let trust_set = if let Some(for_id) = local.get_for_id_from_str_opt(None)? {
+        db.calculate_trust_set(&for_id, &crev_lib::TrustDistanceParams::default())
+    } else {
+        crev_lib::proofdb::TrustSet::default()
+    };
 
     let client = SyncClient::new();
-    let mut stats: Vec<_> = client
+    let mut crates = client
         .crates(ListOptions {
-            sort: Sort::Downloads,
+            sort: match sort {
+                SearchSort::Relevance | SearchSort::Newest => Sort::Relevance,
+                SearchSort::Reviews | SearchSort::Downloads | SearchSort::RecentDownloads => {
+                    Sort::Downloads
+                }
+            },
             per_page: 100,
             page: 1,
             query: Some(query.to_string()),
         })?
-        .crates
+        .crates;
+
+    // crates.io API can't sort by these itself
+    match sort {
+        SearchSort::RecentDownloads => {
+            crates.sort_by(|a, b| a.recent_downloads.cmp(&b.recent_downloads).reverse())
+        }
+        SearchSort::Newest => crates.sort_by(|a, b| a.created_at.cmp(&b.created_at).reverse()),
+        _ => {}
+    }
+
+    let mut stats: Vec<_> = crates
         .iter()
         .map(|crate_| CrateStats {
             name: crate_.name.clone(),
+            version: crate_.max_version.clone(),
+            description: crate_
+                .description
+                .as_ref()
+                .and_then(|d| d.lines().next())
+                .unwrap_or("")
+                .trim()
+                .to_owned(),
             downloads: crate_.downloads,
             proof_count: db.get_package_review_count(
                 PROJECT_SOURCE_CRATES_IO,
                 Some(&crate_.name),
                 None,
             ),
+            trusted_reviewer_count: db
+                .get_package_reviews_for_package(PROJECT_SOURCE_CRATES_IO, Some(&crate_.name), None)
+                .map(|review| &review.from.id)
+                .filter(|id| trust_set.get_effective_trust_level(id) >= crev_data::TrustLevel::Low)
+                .collect::<HashSet<_>>()
+                .len(),
         })
         .collect();
 
-    stats.sort_by(|a, b| {
-        a.proof_count
-            .cmp(&b.proof_count)
-            .then(a.downloads.cmp(&b.downloads))
-            .reverse()
-    });
+    if sort == SearchSort::Reviews {
+        stats.sort_by(|a, b| {
+            a.proof_count
+                .cmp(&b.proof_count)
+                .then(a.downloads.cmp(&b.downloads))
+                .reverse()
+        });
+    }
 
+    let mut term = crate::term::Term::new();
     for stats in stats.iter().take(count) {
-        println!("{:8} {}", stats.proof_count, stats.name);
+        // crates nobody reviewed yet are less interesting
+        let color = if stats.proof_count == 0 {
+            Some(::term::color::BRIGHT_BLACK)
+        } else {
+            None
+        };
+        term.print(
+            format_args!(
+                "{:8} {:3} {:>10} {} {} {}\n",
+                stats.proof_count,
+                stats.trusted_reviewer_count,
+                stats.downloads,
+                stats.name,
+                stats.version,
+                stats.description
+            ),
+            color,
+        )?;
     }
 
     Ok(())
 }
+
+/// Check the proofs of the fetched repository of `url`, or of all of them
+pub fn verify_repos_integrity(url: Option<&str>) -> Result<CommandExitStatus> {
+    let local = Local::auto_create_or_open()?;
+    let repos = match url {
+        Some(url) => vec![local.verify_fetched_repo_integrity(url)?],
+        None => local.verify_all_fetched_repos_integrity()?,
+    };
+
+    let mut nb_broken = 0;
+    for repo in &repos {
+        println!(
+            "{} {} proofs ok, {} broken",
+            repo.url,
+            repo.valid_count,
+            repo.broken.len()
+        );
+        for (path, reason) in &repo.broken {
+            println!("  {}: {}", path.display(), reason);
+        }
+        nb_broken += repo.broken.len();
+    }
+
+    Ok(if nb_broken == 0 {
+        CommandExitStatus::Success
+    } else {
+        eprintln!(
+            "{} broken proof{} found",
+            nb_broken,
+            if nb_broken == 1 { "" } else { "s" }
+        );
+        CommandExitStatus::VerificationFailed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_config_cannot_set_post_verify_hook() -> Result<()> {
+        let project: ProjectConfig = toml::from_str(
+            r#"
+post-verify-hook = "touch pwned"
+
+[verify]
+recursive = true
+post-verify-hook = "touch pwned"
+
+[verify.profiles.ci]
+post-verify-hook = "touch pwned"
+"#,
+        )?;
+
+        let mut args = opts::CrateVerify::default();
+        args.apply_config_defaults(&project.verify.clone())?;
+        assert!(args.recursive);
+        assert_eq!(args.post_verify_hook, None);
+
+        let mut args = opts::CrateVerify::default();
+        args.apply_config_defaults(&project.verify.with_profile("ci")?)?;
+        assert_eq!(args.post_verify_hook, None);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_owner_in_list_keeps_comments_and_other_owners() {
+        let content = "# known owners\n# alice\nalice\n  alice  \nalicea\nbob\n";
+        let (renamed, count) = rename_owner_in_list(content, "alice", "carol");
+        assert_eq!(count, 2);
+        assert_eq!(
+            renamed,
+            "# known owners\n# alice\ncarol\n  carol  \nalicea\nbob\n"
+        );
+
+        let (renamed, count) = rename_owner_in_list(content, "dave", "carol");
+        assert_eq!(count, 0);
+        assert_eq!(renamed, content);
+    }
+
+    #[test]
+    fn diff_stat_counts_dashes_and_pluses_in_hunks_as_changes() {
+        let diff = "\
+diff -r -u /src/foo-0.1.0/src/lib.rs /src/foo-0.2.0/src/lib.rs
+--- /src/foo-0.1.0/src/lib.rs\t2019-01-01
++++ /src/foo-0.2.0/src/lib.rs\t2019-01-02
+@@ -1,3 +1,3 @@
+ fn main() {
+---- x;
++++ y;
++unsafe {}
+ }
+";
+        let files = parse_diff_stat(diff, Path::new("/src/foo-0.2.0"));
+        assert_eq!(
+            files,
+            vec![DiffFileStat {
+                path: "src/lib.rs".into(),
+                insertions: 2,
+                deletions: 1,
+                touches_unsafe: true,
+            }]
+        );
+    }
+}
@@ -25,11 +25,62 @@ pub fn known_owners_count_color(count: u64) -> Option<color::Color> {
     }
 }
 
+/// Render `data` as a QR code, with two rows of modules per line of text
+///
+/// Light modules are drawn as blocks, so that the code shows up
+/// correctly on the (usually dark) terminal background.
+pub fn qr_code_string(data: &[u8]) -> common_failures::Result<String> {
+    use qrcode::{Color as QrColor, QrCode};
+
+    // light modules around the code, 4 as required by the spec
+    const QUIET_ZONE: usize = 4;
+
+    let code =
+        QrCode::new(data).map_err(|e| failure::format_err!("Can't create QR code: {}", e))?;
+    let width = code.width();
+    let colors = code.to_colors();
+    let is_light = |x: usize, y: usize| {
+        if x < QUIET_ZONE || y < QUIET_ZONE {
+            return true;
+        }
+        let (x, y) = (x - QUIET_ZONE, y - QUIET_ZONE);
+        x >= width || y >= width || colors[y * width + x] == QrColor::Light
+    };
+
+    let size = width + 2 * QUIET_ZONE;
+    let mut s = String::new();
+    for y in (0..size).step_by(2) {
+        for x in 0..size {
+            s.push(match (is_light(x, y), y + 1 < size && is_light(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        s.push('\n');
+    }
+    Ok(s)
+}
+
+/// Disables colored output when set to a non-empty value (https://no-color.org)
+pub const NO_COLOR_ENV: &str = "NO_COLOR";
+
+fn is_color_disabled() -> bool {
+    match std::env::var_os(NO_COLOR_ENV) {
+        Some(v) => !v.is_empty(),
+        None => false,
+    }
+}
+
 /// Helper to control (possibly) colored output
 pub struct Term {
     pub stdout_is_tty: bool,
     pub stderr_is_tty: bool,
     pub stdin_is_tty: bool,
+    /// Width of the terminal on stdout, if known
+    pub width: Option<usize>,
+    color_disabled: bool,
     stdout: Option<Box<StdoutTerminal>>,
     #[allow(unused)]
     stderr: Option<Box<StderrTerminal>>,
@@ -39,12 +90,12 @@ fn output_to<O>(
     args: std::fmt::Arguments<'_>,
     color: Option<Color>,
     term: &mut dyn term::Terminal<Output = O>,
-    is_tty: bool,
+    use_color: bool,
 ) -> io::Result<()>
 where
     O: Write,
 {
-    let use_color = is_tty && term.supports_color();
+    let use_color = use_color && term.supports_color();
     if use_color {
         if let Some(color) = color {
             term.fg(color)?
@@ -61,12 +112,19 @@ where
 
 impl Term {
     pub fn new() -> Term {
+        let stdout_is_tty = atty::is(atty::Stream::Stdout);
         Term {
             stdout: term::stdout(),
             stderr: term::stderr(),
             stdin_is_tty: atty::is(atty::Stream::Stdin),
-            stdout_is_tty: atty::is(atty::Stream::Stdout),
+            stdout_is_tty,
+            width: if stdout_is_tty {
+                Some(termimad::terminal_size().0 as usize)
+            } else {
+                None
+            },
             stderr_is_tty: atty::is(atty::Stream::Stderr),
+            color_disabled: is_color_disabled(),
         }
     }
 
@@ -81,7 +139,7 @@ impl Term {
                 fmt,
                 color,
                 (&mut **term) as &mut dyn term::Terminal<Output = _>,
-                self.stdout_is_tty,
+                self.stdout_is_tty && !self.color_disabled,
             )?;
         }
         Ok(())
@@ -99,7 +157,7 @@ impl Term {
                 fmt,
                 color,
                 (&mut **term) as &mut dyn term::Terminal<Output = _>,
-                self.stdout_is_tty,
+                self.stderr_is_tty && !self.color_disabled,
             )?;
         }
         Ok(())
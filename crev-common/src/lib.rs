@@ -199,6 +199,14 @@ pub fn run_with_shell_cmd(
     Ok(run_with_shell_cmd_custom(cmd, arg, false)?.status)
 }
 
+/// Like `run_with_shell_cmd`, but passing any number of paths to `cmd`
+pub fn run_with_shell_cmd_args(
+    cmd: OsString,
+    args: &[&Path],
+) -> io::Result<std::process::ExitStatus> {
+    Ok(shell_cmd_with_args(cmd, args, false)?.status)
+}
+
 pub fn run_with_shell_cmd_capture_stdout(cmd: OsString, arg: Option<&Path>) -> io::Result<Vec<u8>> {
     let output = run_with_shell_cmd_custom(cmd, arg, true)?;
     if !output.status.success() {
@@ -214,41 +222,39 @@ pub fn run_with_shell_cmd_custom(
     cmd: OsString,
     arg: Option<&Path>,
     capture_stdout: bool,
+) -> io::Result<std::process::Output> {
+    let args: Vec<&Path> = arg.into_iter().collect();
+    shell_cmd_with_args(cmd, &args, capture_stdout)
+}
+
+fn shell_cmd_with_args(
+    cmd: OsString,
+    args: &[&Path],
+    capture_stdout: bool,
 ) -> io::Result<std::process::Output> {
     Ok(if cfg!(windows) {
         // cmd.exe /c "..." or cmd.exe /k "..." avoid unescaping "...", which makes .arg()'s built-in escaping problematic:
         // https://github.com/rust-lang/rust/blob/379c380a60e7b3adb6c6f595222cbfa2d9160a20/src/libstd/sys/windows/process.rs#L488
         // We can bypass this by (ab)using env vars.  Bonus points:  invalid unicode still works.
         let mut proc = process::Command::new("cmd.exe");
-        if let Some(arg) = arg {
-            proc.arg("/c").arg("%CREV_CMD% %CREV_ARG%");
-            proc.env("CREV_CMD", &cmd);
-            proc.env("CREV_ARG", arg);
-        } else {
-            proc.arg("/c").arg("%CREV_CMD%");
-            proc.env("CREV_CMD", &cmd);
+        let mut line = "%CREV_CMD%".to_owned();
+        proc.env("CREV_CMD", &cmd);
+        for (i, arg) in args.iter().enumerate() {
+            line.push_str(&format!(" %CREV_ARG{}%", i));
+            proc.env(format!("CREV_ARG{}", i), arg);
         }
+        proc.arg("/c").arg(line);
         proc
     } else if cfg!(unix) {
         let mut proc = process::Command::new("/bin/sh");
-        if let Some(arg) = arg {
-            proc.arg("-c").arg(format!(
-                "{} {}",
-                cmd.clone().into_string().map_err(|_| std::io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "not a valid unicode"
-                ))?,
-                shell_escape::escape(arg.display().to_string().into())
-            ));
-        } else {
-            proc.arg("-c").arg(format!(
-                "{}",
-                cmd.clone().into_string().map_err(|_| std::io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "not a valid unicode"
-                ))?,
-            ));
+        let mut line = cmd
+            .into_string()
+            .map_err(|_| std::io::Error::new(io::ErrorKind::InvalidData, "not a valid unicode"))?;
+        for arg in args {
+            line.push(' ');
+            line.push_str(&shell_escape::escape(arg.display().to_string().into()));
         }
+        proc.arg("-c").arg(line);
         proc
     } else {
         panic!("What platform are you running this on? Please submit a PR!");
@@ -65,6 +65,18 @@ impl Id {
             Id::Crev { id } => id.clone(),
         }
     }
+
+    /// Short, human-verifiable fingerprint of the public key
+    ///
+    /// Eight groups of four hex digits, eg. to compare over the phone.
+    pub fn fingerprint(&self) -> String {
+        let hash = crev_common::blake2b256sum(&self.to_bytes());
+        hash[..16]
+            .chunks(2)
+            .map(|group| format!("{:02X}{:02X}", group[0], group[1]))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 impl fmt::Display for Id {
@@ -191,6 +203,15 @@ impl OwnId {
         }
     }
 
+    /// Generate an Id deterministically from `seed`
+    ///
+    /// Anyone knowing the seed can recreate the Id, so it's only meant
+    /// for tests and reproducible environments.
+    pub fn generate_from_seed(url: Url, seed: &[u8]) -> Self {
+        let sec_key = crev_common::blake2b256sum(seed);
+        Self::new(url, sec_key).expect("32 bytes make a valid secret key")
+    }
+
     pub fn create_signed_trust_proof<'a>(
         &self,
         ids: impl IntoIterator<Item = &'a PubId>,
@@ -4,6 +4,7 @@ use crate::Url;
 use chrono::{self, prelude::*};
 use crev_common;
 use failure::bail;
+use serde::{Deserialize, Serialize};
 use std::{
     default, fmt, fs,
     io::{self, BufRead},
@@ -93,7 +94,7 @@ pub(crate) struct Serialized {
 }
 
 /// Content is an enumerator of possible proof contents
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Content {
     Trust(Trust),
     Package(Box<review::Package>),
@@ -169,8 +170,15 @@ impl Content {
     }
 
     pub fn sign_by(&self, id: &crate::id::OwnId) -> Result<Proof> {
+        self.sign_with(|body| Ok(id.sign(body)))
+    }
+
+    /// Sign using a custom signing function (eg. an external signer)
+    ///
+    /// `sign` is given the proof body and returns a raw signature of it.
+    pub fn sign_with(&self, sign: impl FnOnce(&[u8]) -> Result<Vec<u8>>) -> Result<Proof> {
         let body = self.to_string();
-        let signature = id.sign(&body.as_bytes());
+        let signature = sign(&body.as_bytes())?;
         Ok(Proof {
             digest: crev_common::blake2b256sum(&body.as_bytes()),
             body,
@@ -262,7 +270,7 @@ impl Content {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// A `Proof` with it's content parsed and ready.
 pub struct Proof {
     pub body: String,
@@ -435,6 +443,16 @@ impl Proof {
         Ok(v)
     }
 
+    /// Like `parse`, but with a result for each proof
+    ///
+    /// One proof with invalid content doesn't prevent reading the other ones.
+    pub fn parse_each(reader: impl io::Read) -> Result<Vec<Result<Self>>> {
+        Ok(Serialized::parse(reader)?
+            .iter()
+            .map(Serialized::to_parsed)
+            .collect())
+    }
+
     pub fn signature(&self) -> &str {
         self.signature.trim()
     }
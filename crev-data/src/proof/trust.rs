@@ -91,7 +91,7 @@ pub struct Trust {
     pub trust: TrustLevel,
     #[serde(skip_serializing_if = "String::is_empty", default = "Default::default")]
     #[builder(default = "Default::default()")]
-    comment: String,
+    pub comment: String,
 }
 
 impl Trust {
@@ -132,6 +132,50 @@ pub fn verify_works() -> Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn parse_each_keeps_going_past_invalid_proofs() -> Result<()> {
+    let (_id, proof) = generate_id_and_proof()?;
+    let s = format!(
+        "{}{}",
+        r#"
+-----BEGIN CODE REVIEW-----
+foo
+-----BEGIN CODE REVIEW SIGNATURE-----
+sig
+-----END CODE REVIEW-----
+"#,
+        proof
+    );
+
+    let proofs = Proof::parse_each(s.as_bytes())?;
+    assert_eq!(proofs.len(), 2);
+    assert!(proofs[0].is_err());
+    proofs[1].as_ref().expect("valid proof").verify()?;
+    assert!(Proof::parse(s.as_bytes()).is_err());
+
+    Ok(())
+}
+
+#[test]
+pub fn id_fingerprint() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+
+    let fingerprint = a.id.id.fingerprint();
+    assert_eq!(fingerprint.len(), 8 * 4 + 7);
+    assert_eq!(fingerprint.split(' ').count(), 8);
+    assert_eq!(fingerprint, a.id.id.fingerprint());
+    assert_ne!(fingerprint, b.id.id.fingerprint());
+
+    let id = crate::Id::crevid_from_str("cSGtRagJCK4Ql9SIaHTJz9RLsBd3KMJbS7W2FxxUAlY")?;
+    assert_eq!(
+        id.fingerprint(),
+        crate::Id::crevid_from_str(&id.to_string())?.fingerprint()
+    );
+
+    Ok(())
+}
+
 #[test]
 pub fn ensure_serializes_to_valid_proof_works() -> Result<()> {
     let a = OwnId::generate_for_git_url("https://a");
@@ -161,3 +205,14 @@ pub fn ensure_serializes_to_valid_proof_works() -> Result<()> {
         .is_err());
     Ok(())
 }
+
+#[test]
+fn generate_from_seed_is_deterministic() {
+    let url = Url::new_git("https://example.com/crev-proofs".into());
+    let a = OwnId::generate_from_seed(url.clone(), b"seed");
+    let b = OwnId::generate_from_seed(url.clone(), b"seed");
+    let c = OwnId::generate_from_seed(url, b"other seed");
+
+    assert_eq!(a.id.id, b.id.id);
+    assert_ne!(a.id.id, c.id.id);
+}
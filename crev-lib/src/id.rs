@@ -4,7 +4,10 @@ use crev_common::{
     rand::random_vec,
     serde::{as_base64, from_base64},
 };
-use crev_data::id::{OwnId, PubId};
+use crev_data::{
+    id::{OwnId, PubId},
+    proof,
+};
 use failure::{bail, format_err};
 use miscreant;
 use num_cpus;
@@ -13,6 +16,10 @@ use serde_yaml;
 use std::{self, fmt, io::Write, path::Path};
 
 const CURRENT_LOCKED_ID_SERIALIZATION_VERSION: i64 = -1;
+const ARMOR_BEGIN: &str = "-----BEGIN CREV LOCKED ID-----";
+const ARMOR_END: &str = "-----END CREV LOCKED ID-----";
+const ARMOR_CHECKSUM_HEADER: &str = "Checksum:";
+const ARMOR_LINE_LEN: usize = 64;
 pub type PassphraseFn<'a> = &'a dyn Fn() -> std::io::Result<String>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -61,7 +68,68 @@ impl std::str::FromStr for LockedId {
     }
 }
 
+fn armor_checksum(bytes: &[u8]) -> String {
+    crev_common::base64_encode(&crev_common::blake2b256sum(bytes)[..6])
+}
+
 impl LockedId {
+    /// Serialize wrapped in BEGIN/END markers, with a checksum
+    ///
+    /// The content is base64-encoded, so it survives being pasted
+    /// through tools that mangle whitespace.
+    pub fn to_armored_string(&self) -> Result<String> {
+        let yaml = serde_yaml::to_string(self)?;
+        let encoded = crev_common::base64_encode(&yaml);
+
+        let mut armored = format!(
+            "{}\n{} {}\n\n",
+            ARMOR_BEGIN,
+            ARMOR_CHECKSUM_HEADER,
+            armor_checksum(yaml.as_bytes())
+        );
+        for line in encoded.as_bytes().chunks(ARMOR_LINE_LEN) {
+            armored.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+            armored.push('\n');
+        }
+        armored.push_str(ARMOR_END);
+        Ok(armored)
+    }
+
+    /// Parse a `LockedId`, either plain or wrapped by `to_armored_string`
+    pub fn from_str_maybe_armored(s: &str) -> Result<LockedId> {
+        let begin = match s.find(ARMOR_BEGIN) {
+            Some(begin) => begin + ARMOR_BEGIN.len(),
+            None => return Ok(s.parse()?),
+        };
+        let end = match s[begin..].find(ARMOR_END) {
+            Some(end) => begin + end,
+            None => bail!("Armored Id is truncated: `{}` marker missing", ARMOR_END),
+        };
+        let body = s[begin..end].trim_start();
+
+        if !body.starts_with(ARMOR_CHECKSUM_HEADER) {
+            bail!("Armored Id has no `{}` header", ARMOR_CHECKSUM_HEADER);
+        }
+        let body = &body[ARMOR_CHECKSUM_HEADER.len()..];
+        let mut parts = body.trim_start().splitn(2, char::is_whitespace);
+        let checksum = parts.next().unwrap_or("");
+        let encoded: String = parts
+            .next()
+            .unwrap_or("")
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        let yaml = crev_common::base64_decode(&encoded)
+            .map_err(|e| format_err!("Armored Id is corrupted: {}", e))?;
+        if armor_checksum(&yaml) != checksum {
+            bail!("Armored Id is corrupted: checksum mismatch");
+        }
+        let yaml =
+            String::from_utf8(yaml).map_err(|e| format_err!("Armored Id is corrupted: {}", e))?;
+        Ok(yaml.parse()?)
+    }
+
     pub fn from_own_id(own_id: &OwnId, passphrase: &str) -> Result<LockedId> {
         use miscreant::aead::Aead;
 
@@ -190,3 +258,52 @@ impl LockedId {
         }
     }
 }
+
+/// Something that can sign proofs on behalf of the current Id
+pub enum Signer {
+    /// Unlocked, locally stored key
+    Local(OwnId),
+    /// External command (eg. talking to a hardware token)
+    ///
+    /// The command is called with a path to a file containing
+    /// the proof body, and should print a base64-encoded signature.
+    External { id: PubId, cmd: String },
+}
+
+impl Signer {
+    pub fn as_pubid(&self) -> &PubId {
+        match self {
+            Signer::Local(own_id) => own_id.as_pubid(),
+            Signer::External { id, .. } => id,
+        }
+    }
+
+    pub fn sign(&self, content: &proof::Content) -> Result<proof::Proof> {
+        match self {
+            Signer::Local(own_id) => Ok(content.sign_by(own_id)?),
+            Signer::External { cmd, .. } => {
+                let proof = content.sign_with(|body| {
+                    let dir = tempdir::TempDir::new("crev")?;
+                    let body_path = dir.path().join("proof");
+                    std::fs::write(&body_path, body)?;
+                    let stdout = crev_common::run_with_shell_cmd_capture_stdout(
+                        cmd.into(),
+                        Some(&body_path),
+                    )
+                    .map_err(|e| format_err!("Signer command `{}` failed: {}", cmd, e))?;
+                    Ok(crev_common::base64_decode(
+                        String::from_utf8(stdout)?.trim(),
+                    )?)
+                })?;
+                proof.verify().map_err(|e| {
+                    format_err!(
+                        "Signature returned by `{}` doesn't match the current Id: {}",
+                        cmd,
+                        e
+                    )
+                })?;
+                Ok(proof)
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub mod id;
 pub mod local;
 pub(crate) mod prelude;
 pub mod proof;
+mod proof_index;
 pub mod proofdb;
 pub mod repo;
 pub mod staging;
@@ -20,7 +21,7 @@ use std::{
 };
 
 pub use self::local::Local;
-pub use crate::proofdb::{ProofDB, TrustDistanceParams};
+pub use crate::proofdb::{ProofDB, ProofDBStats, ReviewVerdict, TrustDistanceParams, TrustPath};
 pub use activity::{ReviewActivity, ReviewMode};
 
 /// Trait representing a place that can keep proofs
@@ -64,6 +65,15 @@ impl TrustProofType {
             Untrust => crev_data::Review::new_none(),
         }
     }
+
+    pub fn to_trust_level(self) -> crev_data::TrustLevel {
+        use self::TrustProofType::*;
+        match self {
+            Trust => crev_data::TrustLevel::Medium,
+            Distrust => crev_data::TrustLevel::Distrust,
+            Untrust => crev_data::TrustLevel::None,
+        }
+    }
 }
 
 /// Verification requirements
@@ -73,6 +83,8 @@ pub struct VerificationRequirements {
     pub understanding: crev_data::Level,
     pub thoroughness: crev_data::Level,
     pub redundancy: u64,
+    /// Only reviews created at or after this date count
+    pub since: Option<crev_data::proof::Date>,
 }
 
 impl Default for VerificationRequirements {
@@ -82,6 +94,7 @@ impl Default for VerificationRequirements {
             understanding: Default::default(),
             thoroughness: Default::default(),
             redundancy: 1,
+            since: None,
         }
     }
 }
@@ -175,6 +188,35 @@ where
     ))
 }
 
+/// Digest types (as in `digest-type` of proofs) that package digests
+/// can be calculated with
+pub const SUPPORTED_DIGEST_TYPES: &[&str] = &["blake2b", "sha256"];
+
+/// Like `get_dir_digest`, but using the given digest type
+///
+/// Returns `None` if the digest type is not supported.
+pub fn get_dir_digest_of_type<H1>(
+    path: &Path,
+    ignore_list: &HashSet<PathBuf, H1>,
+    digest_type: &str,
+) -> Result<Option<Digest>>
+where
+    H1: std::hash::BuildHasher + std::default::Default,
+{
+    let digest = match digest_type {
+        "blake2b" => crev_recursive_digest::get_recursive_digest_for_dir::<
+            crev_common::Blake2b256,
+            H1,
+        >(path, ignore_list)?,
+        "sha256" => crev_recursive_digest::get_recursive_digest_for_dir::<sha2::Sha256, H1>(
+            path,
+            ignore_list,
+        )?,
+        _ => return Ok(None),
+    };
+    Ok(Some(Digest::from_vec(digest)))
+}
+
 pub fn get_recursive_digest_for_git_dir<H>(
     root_path: &Path,
     ignore_list: &HashSet<PathBuf, H>,
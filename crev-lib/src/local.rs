@@ -1,10 +1,12 @@
-use crate::TrustProofType;
 use crate::{
     activity::ReviewActivity,
-    id::{self, LockedId, PassphraseFn},
+    id::{self, LockedId, PassphraseFn, Signer},
     prelude::*,
+    proof_index::ProofIndex,
+    proofdb::TrustSet,
     util, ProofDB, ProofStore,
 };
+use chrono::TimeZone;
 use crev_common::{
     self, sanitize_name_for_fs, sanitize_url_for_fs,
     serde::{as_base64, from_base64},
@@ -12,7 +14,7 @@ use crev_common::{
 use crev_data::{
     id::OwnId,
     proof::{self, trust::TrustLevel},
-    Id, PubId, Url,
+    Id, Level, PubId, Url,
 };
 use default::default;
 use directories::ProjectDirs;
@@ -24,12 +26,11 @@ use serde::{Deserialize, Serialize};
 use serde_yaml;
 use std::{
     cell::RefCell,
-    collections::HashSet,
+    collections::{BTreeMap, BTreeSet, HashSet},
     ffi::OsString,
     fs,
-    io::{BufRead, Write},
+    io::{self, BufRead, Write},
     path::{Path, PathBuf},
-    str::FromStr,
 };
 
 const CURRENT_USER_CONFIG_SERIALIZATION_VERSION: i64 = -1;
@@ -76,6 +77,286 @@ pub struct UserConfig {
         default = "Option::default"
     )]
     pub open_cmd: Option<String>,
+
+    #[serde(
+        rename = "signer-cmd",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub signer_cmd: Option<String>,
+
+    #[serde(
+        rename = "publish-message",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub publish_message: Option<String>,
+
+    #[serde(
+        rename = "editor",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub editor: Option<String>,
+
+    /// Base URL of the crates.io API (or a mirror of it)
+    #[serde(
+        rename = "crates-io-api",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub crates_io_api: Option<String>,
+
+    /// Path of a file pre-filled into the comment of new reviews
+    #[serde(
+        rename = "review-template",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub review_template: Option<String>,
+
+    /// Command showing `crate diff`s instead of `diff`, given the two source directories
+    #[serde(
+        rename = "diff-cmd",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub diff_cmd: Option<String>,
+
+    /// Shell command run after `crate verify`, see `crate verify --post-verify-hook`
+    ///
+    /// Not part of `verify`, so that the `.crev/config.toml` of a project
+    /// can't make verifying it run a command.
+    #[serde(
+        rename = "post-verify-hook",
+        skip_serializing_if = "is_none_or_empty",
+        default = "Option::default"
+    )]
+    pub post_verify_hook: Option<String>,
+
+    #[serde(
+        rename = "verify",
+        skip_serializing_if = "VerifyConfig::is_empty",
+        default = "VerifyConfig::default"
+    )]
+    pub verify: VerifyConfig,
+}
+
+/// Default flags for `crate verify`, used when not given on the command line
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct VerifyConfig {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub verbose: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub skip_verified: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub skip_known_owners: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub skip_indirect: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub recursive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub skip_geiger: Option<bool>,
+    /// Minimum trust level of reviewers (`--trust`)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub trust: Option<Level>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub redundancy: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub understanding: Option<Level>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub thoroughness: Option<Level>,
+    /// Number of crates scanned in parallel
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub jobs: Option<usize>,
+    /// Extra columns to display, see `crate verify --columns`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub columns: Option<Vec<String>>,
+    /// Named sets of flags, picked with `crate verify --profile-name`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub profiles: BTreeMap<String, VerifyConfig>,
+}
+
+impl VerifyConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fill in values not set in `self` from `other`
+    ///
+    /// Profiles defined in both are merged the same way.
+    pub fn or(self, other: Self) -> Self {
+        let mut profiles = other.profiles;
+        for (name, profile) in self.profiles {
+            let profile = match profiles.remove(&name) {
+                Some(other_profile) => profile.or(other_profile),
+                None => profile,
+            };
+            profiles.insert(name, profile);
+        }
+        Self {
+            verbose: self.verbose.or(other.verbose),
+            skip_verified: self.skip_verified.or(other.skip_verified),
+            skip_known_owners: self.skip_known_owners.or(other.skip_known_owners),
+            skip_indirect: self.skip_indirect.or(other.skip_indirect),
+            recursive: self.recursive.or(other.recursive),
+            skip_geiger: self.skip_geiger.or(other.skip_geiger),
+            trust: self.trust.or(other.trust),
+            redundancy: self.redundancy.or(other.redundancy),
+            understanding: self.understanding.or(other.understanding),
+            thoroughness: self.thoroughness.or(other.thoroughness),
+            jobs: self.jobs.or(other.jobs),
+            columns: self.columns.or(other.columns),
+            profiles,
+        }
+    }
+
+    /// Flags of the profile `name`, falling back to the ones set outside of profiles
+    pub fn with_profile(mut self, name: &str) -> Result<Self> {
+        let profile = match self.profiles.remove(name) {
+            Some(profile) => profile,
+            None => bail!("Verify profile `{}` not found in the config", name),
+        };
+        Ok(Self {
+            profiles: BTreeMap::new(),
+            ..profile
+        }
+        .or(self))
+    }
+
+    fn field(&self, name: &str) -> Option<Option<bool>> {
+        Some(match name {
+            "verbose" => self.verbose,
+            "skip-verified" => self.skip_verified,
+            "skip-known-owners" => self.skip_known_owners,
+            "skip-indirect" => self.skip_indirect,
+            "recursive" => self.recursive,
+            "skip-geiger" => self.skip_geiger,
+            _ => return None,
+        })
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut Option<bool>> {
+        Some(match name {
+            "verbose" => &mut self.verbose,
+            "skip-verified" => &mut self.skip_verified,
+            "skip-known-owners" => &mut self.skip_known_owners,
+            "skip-indirect" => &mut self.skip_indirect,
+            "recursive" => &mut self.recursive,
+            "skip-geiger" => &mut self.skip_geiger,
+            _ => return None,
+        })
+    }
+}
+
+fn parse_opt_string(value: &str) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_owned())
+    }
+}
+
+fn parse_opt_value<T: std::str::FromStr>(key: &str, value: &str) -> Result<Option<T>> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(value.parse().map_err(|_| {
+        format_err!("Invalid value for {}: `{}`", key, value)
+    })?))
+}
+
+impl UserConfig {
+    /// Get the value of a config `key` (eg. `open-cmd` or `verify.recursive`)
+    ///
+    /// Returns `None` if the key is not set.
+    pub fn get_value(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key {
+            "current-id" => self.current_id.as_ref().map(|id| id.to_string()),
+            "open-cmd" => self.open_cmd.clone(),
+            "signer-cmd" => self.signer_cmd.clone(),
+            "editor" => self.editor.clone(),
+            "publish-message" => self.publish_message.clone(),
+            "crates-io-api" => self.crates_io_api.clone(),
+            "review-template" => self.review_template.clone(),
+            "diff-cmd" => self.diff_cmd.clone(),
+            "post-verify-hook" => self.post_verify_hook.clone(),
+            "verify.trust" => self.verify.trust.map(|level| level.to_string()),
+            "verify.redundancy" => self.verify.redundancy.map(|n| n.to_string()),
+            "verify.understanding" => self.verify.understanding.map(|level| level.to_string()),
+            "verify.thoroughness" => self.verify.thoroughness.map(|level| level.to_string()),
+            "verify.jobs" => self.verify.jobs.map(|n| n.to_string()),
+            "verify.columns" => self
+                .verify
+                .columns
+                .as_ref()
+                .map(|columns| columns.join(",")),
+            _ => key_verify_field(key)
+                .and_then(|field| self.verify.field(field))
+                .ok_or_else(|| format_err!("Unknown config key: {}", key))?
+                .map(|v| v.to_string()),
+        })
+    }
+
+    /// Set the value of a config `key`; an empty `value` unsets it
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "current-id" => {
+                self.current_id = if value.is_empty() {
+                    None
+                } else {
+                    Some(Id::crevid_from_str(value)?)
+                }
+            }
+            "open-cmd" => self.open_cmd = parse_opt_string(value),
+            "signer-cmd" => self.signer_cmd = parse_opt_string(value),
+            "editor" => self.editor = parse_opt_string(value),
+            "publish-message" => self.publish_message = parse_opt_string(value),
+            "crates-io-api" => self.crates_io_api = parse_opt_string(value),
+            "review-template" => self.review_template = parse_opt_string(value),
+            "diff-cmd" => self.diff_cmd = parse_opt_string(value),
+            "post-verify-hook" => self.post_verify_hook = parse_opt_string(value),
+            "verify.trust" => self.verify.trust = parse_opt_value(key, value)?,
+            "verify.redundancy" => self.verify.redundancy = parse_opt_value(key, value)?,
+            "verify.understanding" => self.verify.understanding = parse_opt_value(key, value)?,
+            "verify.thoroughness" => self.verify.thoroughness = parse_opt_value(key, value)?,
+            "verify.jobs" => self.verify.jobs = parse_opt_value(key, value)?,
+            "verify.columns" => {
+                self.verify.columns = parse_opt_string(value).map(|value| {
+                    value
+                        .split(',')
+                        .map(|column| column.trim().to_owned())
+                        .collect()
+                })
+            }
+            _ => {
+                let field = key_verify_field(key)
+                    .and_then(|field| self.verify.field_mut(field))
+                    .ok_or_else(|| format_err!("Unknown config key: {}", key))?;
+                *field = if value.is_empty() {
+                    None
+                } else {
+                    Some(value.parse().map_err(|_| {
+                        format_err!(
+                            "Invalid value for {}: `{}`, expected `true` or `false`",
+                            key,
+                            value
+                        )
+                    })?)
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+fn key_verify_field(key: &str) -> Option<&str> {
+    let mut parts = key.splitn(2, '.');
+    match (parts.next(), parts.next()) {
+        (Some("verify"), Some(field)) => Some(field),
+        _ => None,
+    }
 }
 
 impl Default for UserConfig {
@@ -85,6 +366,14 @@ impl Default for UserConfig {
             current_id: None,
             host_salt: generete_salt(),
             open_cmd: None,
+            signer_cmd: None,
+            publish_message: None,
+            editor: None,
+            crates_io_api: None,
+            review_template: None,
+            diff_cmd: None,
+            post_verify_hook: None,
+            verify: VerifyConfig::default(),
         }
     }
 }
@@ -113,6 +402,10 @@ impl UserConfig {
     }
 }
 
+/// Environment variable that, when set, overrides the directory
+/// `Local` uses instead of `~/.config/crev`
+pub const ROOT_DIR_ENV: &str = "CREV_ROOT_DIR";
+
 /// Local config stored in `~/.config/crev`
 ///
 /// This managed IDs, local proof repository, etc.
@@ -125,17 +418,27 @@ pub struct Local {
 
 impl Local {
     #[allow(clippy::new_ret_no_self)]
-    fn new() -> Result<Self> {
-        let proj_dir = ProjectDirs::from("", "", "crev")
-            .expect("no valid home directory path could be retrieved from the operating system");
-        let root_path = proj_dir.config_dir().into();
-        let cache_path = proj_dir.cache_dir().into();
-        Ok(Self {
+    pub(crate) fn new() -> Result<Self> {
+        let (root_path, cache_path) = if let Some(root_path) = std::env::var_os(ROOT_DIR_ENV) {
+            let root_path = PathBuf::from(root_path);
+            let cache_path = root_path.join("cache");
+            (root_path, cache_path)
+        } else {
+            let proj_dir = ProjectDirs::from("", "", "crev").expect(
+                "no valid home directory path could be retrieved from the operating system",
+            );
+            (proj_dir.config_dir().into(), proj_dir.cache_dir().into())
+        };
+        Ok(Self::with_paths(root_path, cache_path))
+    }
+
+    fn with_paths(root_path: PathBuf, cache_path: PathBuf) -> Self {
+        Self {
             root_path,
             cache_path,
             cur_url: RefCell::new(None),
             user_config: RefCell::new(None),
-        })
+        }
     }
 
     pub fn get_root_cache_dir(&self) -> &Path {
@@ -143,7 +446,11 @@ impl Local {
     }
 
     pub fn auto_open() -> Result<Self> {
-        let repo = Self::new()?;
+        Self::new()?.open()
+    }
+
+    fn open(self) -> Result<Self> {
+        let repo = self;
         fs::create_dir_all(&repo.cache_remotes_path())?;
         if !repo.root_path.exists() || !repo.user_config_path().exists() {
             bail!("User config not-initialized. Use `crev id new` to generate CrevID.");
@@ -154,7 +461,11 @@ impl Local {
     }
 
     pub fn auto_create() -> Result<Self> {
-        let repo = Self::new()?;
+        Self::new()?.create()
+    }
+
+    fn create(self) -> Result<Self> {
+        let repo = self;
         fs::create_dir_all(&repo.root_path)?;
         fs::create_dir_all(&repo.cache_remotes_path())?;
 
@@ -169,12 +480,20 @@ impl Local {
     }
 
     pub fn auto_create_or_open() -> Result<Self> {
-        let repo = Self::new()?;
-        let config_path = repo.user_config_path();
-        if config_path.exists() {
-            Self::auto_open()
+        Self::new()?.create_or_open()
+    }
+
+    /// Like `auto_create_or_open`, but in `root_path` (with the cache in
+    /// `root_path/cache`), regardless of `CREV_ROOT_DIR`
+    pub fn create_or_open_at(root_path: &Path) -> Result<Self> {
+        Self::with_paths(root_path.into(), root_path.join("cache")).create_or_open()
+    }
+
+    fn create_or_open(self) -> Result<Self> {
+        if self.user_config_path().exists() {
+            self.open()
         } else {
-            Self::auto_create()
+            self.create()
         }
     }
 
@@ -243,11 +562,22 @@ impl Local {
     }
 
     pub fn list_ids(&self) -> Result<Vec<PubId>> {
+        Ok(self
+            .list_locked_ids()?
+            .iter()
+            .map(LockedId::to_pubid)
+            .collect())
+    }
+
+    /// All own Ids stored locally, still locked
+    pub fn list_locked_ids(&self) -> Result<Vec<LockedId>> {
         let ids_path = self.user_ids_path();
         let mut ids = vec![];
+        if !ids_path.exists() {
+            return Ok(ids);
+        }
         for dir_entry in std::fs::read_dir(&ids_path)? {
-            let locked_id = LockedId::read_from_yaml_file(&dir_entry?.path())?;
-            ids.push(locked_id.to_pubid())
+            ids.push(LockedId::read_from_yaml_file(&dir_entry?.path())?);
         }
 
         Ok(ids)
@@ -261,6 +591,37 @@ impl Local {
         self.cache_path.join("remotes")
     }
 
+    /// File listing the urls of proof repositories fetched on request, one per line
+    fn cache_explicit_remotes_path(&self) -> PathBuf {
+        self.cache_path.join("explicit-remotes")
+    }
+
+    fn read_explicit_remotes(&self) -> Result<BTreeSet<String>> {
+        let path = self.cache_explicit_remotes_path();
+        if !path.exists() {
+            return Ok(BTreeSet::new());
+        }
+        Ok(crev_common::read_file_to_string(&path)?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    /// Remember that the user asked for the proofs of `url`, so they are never pruned
+    fn record_explicit_remote(&self, url: &str) -> Result<()> {
+        let mut urls = self.read_explicit_remotes()?;
+        if urls.insert(url.to_owned()) {
+            let mut content = String::new();
+            for url in urls {
+                content.push_str(&url);
+                content.push('\n');
+            }
+            crev_common::store_str_to_file(&self.cache_explicit_remotes_path(), &content)?;
+        }
+        Ok(())
+    }
+
     fn cache_activity_path(&self) -> PathBuf {
         self.cache_path.join("activity")
     }
@@ -337,6 +698,14 @@ impl Local {
         Ok(config.current_id)
     }
 
+    /// Proofs authored by `id`, from its local proof repository
+    pub fn get_own_proofs_of(&self, id: &PubId) -> Result<Vec<proof::Proof>> {
+        let dir = self.get_proofs_dir_path_for_url(&id.url)?;
+        Ok(proofs_iter_for_path(dir)
+            .filter(|proof| proof.content.author_id() == id.id)
+            .collect())
+    }
+
     pub fn read_locked_id(&self, id: &Id) -> Result<LockedId> {
         let path = self.id_path(&id);
         LockedId::read_from_yaml_file(&path)
@@ -367,6 +736,21 @@ impl Local {
             .ok_or_else(|| format_err!("Current Id not set"))
     }
 
+    /// Get a `Signer` for the current Id
+    ///
+    /// Uses the `signer-cmd` from the user config if set, and only asks
+    /// for a passphrase to unlock the local key otherwise.
+    pub fn read_current_signer(&self, passphrase_callback: PassphraseFn<'_>) -> Result<Signer> {
+        let config = self.load_user_config()?;
+        Ok(match config.signer_cmd {
+            Some(ref cmd) if !cmd.is_empty() => Signer::External {
+                id: self.read_current_locked_id()?.to_pubid(),
+                cmd: cmd.clone(),
+            },
+            _ => Signer::Local(self.read_current_unlocked_id(passphrase_callback)?),
+        })
+    }
+
     pub fn read_unlocked_id(
         &self,
         id: &Id,
@@ -395,6 +779,26 @@ impl Local {
         id.save_to(&path)
     }
 
+    /// Re-encrypt the current Id with a new passphrase
+    ///
+    /// The new locked Id replaces the old file atomically.
+    pub fn change_current_id_passphrase(
+        &self,
+        passphrase_callback: PassphraseFn<'_>,
+        new_passphrase_callback: PassphraseFn<'_>,
+    ) -> Result<()> {
+        let own_id = self.read_current_unlocked_id(passphrase_callback)?;
+        let new_passphrase = new_passphrase_callback()?;
+        let locked = id::LockedId::from_own_id(&own_id, &new_passphrase)?;
+
+        let path = self.id_path(&own_id.id.id);
+        crev_common::store_to_file_with(&path, |w| write!(w, "{}", locked))??;
+        // see `LockedId::save_to`
+        let _ = util::chmod_path_to_600(&path);
+
+        Ok(())
+    }
+
     /// Git clone or init new remote Github crev-proof repo
     pub fn clone_proof_dir_from_git(
         &self,
@@ -526,7 +930,8 @@ impl Local {
         &self,
         from_id: &PubId,
         id_strings: Vec<String>,
-        trust_or_distrust: TrustProofType,
+        trust_level: TrustLevel,
+        comment: Option<String>,
     ) -> Result<proof::Content> {
         if id_strings.is_empty() {
             bail!("No ids given.");
@@ -550,14 +955,13 @@ impl Local {
             }
         }
 
-        let trust = from_id.create_trust_proof(
-            &pub_ids,
-            match trust_or_distrust {
-                TrustProofType::Trust => TrustLevel::Medium,
-                TrustProofType::Distrust => TrustLevel::Distrust,
-                TrustProofType::Untrust => TrustLevel::None,
-            },
-        )?;
+        let mut trust = from_id.create_trust_proof(&pub_ids, trust_level)?;
+
+        // with the comment given upfront, there's nothing left to edit
+        if let Some(comment) = comment {
+            trust.comment = comment;
+            return Ok(trust.into());
+        }
 
         // TODO: Look up previous trust proof?
         Ok(util::edit_proof_content_iteractively(
@@ -568,6 +972,7 @@ impl Local {
     }
 
     pub fn fetch_url(&self, url: &str) -> Result<()> {
+        self.record_explicit_remote(url)?;
         let mut db = self.load_db()?;
         if let Some(dir) = self.fetch_proof_repo_import_and_print_counts(url, &mut db) {
             let mut db = ProofDB::new();
@@ -580,10 +985,15 @@ impl Local {
         Ok(())
     }
 
+    /// Fetch proof repositories of all trusted Ids
+    ///
+    /// With `prune`, local copies of repositories with proofs only from Ids
+    /// that are no longer trusted are removed afterwards.
     pub fn fetch_trusted(
         &self,
         trust_params: crate::TrustDistanceParams,
         for_id: Option<&str>,
+        prune: bool,
     ) -> Result<()> {
         let mut already_fetched_ids = HashSet::new();
         let mut already_fetched_urls = HashSet::new();
@@ -617,6 +1027,43 @@ impl Local {
                 }
             }
         }
+
+        if prune {
+            let trust_set = db.calculate_trust_set(&for_id, &trust_params);
+            self.prune_untrusted_remotes(&trust_set)?;
+        }
+        Ok(())
+    }
+
+    /// Remove local copies of fetched proof repositories whose proofs are all
+    /// from Ids not in `trust_set`
+    ///
+    /// Repositories fetched on request (`fetch_url`, `clone_remote_git`), and
+    /// ones without any proof, are kept.
+    fn prune_untrusted_remotes(&self, trust_set: &TrustSet) -> Result<()> {
+        let remotes_path = self.cache_remotes_path();
+        if !remotes_path.exists() {
+            return Ok(());
+        }
+        let explicit_remotes = self.read_explicit_remotes()?;
+        for entry in fs::read_dir(remotes_path)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            match fetched_repo_url(&path) {
+                Ok(Some(url)) if !explicit_remotes.contains(&url) => {}
+                _ => continue,
+            }
+            let author_ids: HashSet<Id> = proofs_iter_for_path(path.clone())
+                .map(|proof| proof.content.author_id())
+                .collect();
+            if author_ids.is_empty() || author_ids.iter().any(|id| trust_set.contains_trusted(id)) {
+                continue;
+            }
+            fs::remove_dir_all(&path)?;
+            eprintln!("Pruned {}", path.display());
+        }
         Ok(())
     }
 
@@ -687,6 +1134,92 @@ impl Local {
         Ok(dir)
     }
 
+    /// Clone or update a git repository that doesn't hold proofs (eg. a database of advisories)
+    ///
+    /// It's kept in the cache directory `name`. Returns the local path.
+    pub fn fetch_git_into_cache(&self, url: &str, name: &str) -> Result<PathBuf> {
+        let dir = self.get_root_cache_dir().join(name);
+
+        if dir.exists() {
+            let repo = git2::Repository::open(&dir)?;
+            util::git::fetch_and_checkout_git_repo(&repo)?
+        } else {
+            git2::Repository::clone(url, &dir)?;
+        }
+
+        Ok(dir)
+    }
+
+    /// Clone a git proof repository into the cache, for the first time
+    ///
+    /// Unlike fetching, fails if it was already cloned. Returns the local path.
+    pub fn clone_remote_git(&self, url: &str) -> Result<PathBuf> {
+        let dir = self.get_remote_git_cache_path(url)?;
+        if dir.exists() {
+            bail!(
+                "Proofs from {} are already cloned to {}; update them with `cargo crev repo fetch url {}`",
+                url,
+                dir.display(),
+                url
+            );
+        }
+        git2::Repository::clone(url, &dir)?;
+        self.record_explicit_remote(url)?;
+
+        Ok(dir)
+    }
+
+    /// Fetch proofs published over plain HTTP(S)
+    ///
+    /// The url can point to a `.crev` file, or to a `.tar.gz` archive
+    /// of a proof repository. Returns the directory proofs were stored in.
+    pub fn fetch_remote_http(&self, url: &str) -> Result<PathBuf> {
+        let dir = self.get_remote_git_cache_path(url)?;
+
+        // `use_sys_proxy` picks up `HTTP_PROXY` and `HTTPS_PROXY`
+        let mut resp = reqwest::Client::builder()
+            .use_sys_proxy()
+            .build()?
+            .get(url)
+            .send()?
+            .error_for_status()?;
+        let is_archive = is_archive_url(url)
+            || resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.contains("gzip"))
+                .unwrap_or(false);
+        let mut body = vec![];
+        resp.copy_to(&mut body)?;
+
+        // replace the previous content only once everything was downloaded;
+        // unpacked next to the remotes, so it can be moved in place
+        let tmp_dir = tempdir::TempDir::new_in(&self.cache_path, "fetch-http")?;
+        if is_archive {
+            tar::Archive::new(flate2::read::GzDecoder::new(&body[..])).unpack(tmp_dir.path())?;
+        } else {
+            fs::write(tmp_dir.path().join("proofs.crev"), &body)?;
+        }
+        // so `fetch_all` knows where to refresh it from
+        fs::write(tmp_dir.path().join(HTTP_REMOTE_URL_FILE), url)?;
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::rename(tmp_dir.into_path(), &dir)?;
+
+        Ok(dir)
+    }
+
+    /// Fetch proofs from `url`, over git or plain HTTP(S), depending on the url
+    pub fn fetch_remote(&self, url: &str) -> Result<PathBuf> {
+        if is_http_proofs_url(url) {
+            self.fetch_remote_http(url)
+        } else {
+            self.fetch_remote_git(url)
+        }
+    }
+
     pub fn fetch_proof_repo_import_and_print_counts(
         &self,
         url: &str,
@@ -696,7 +1229,7 @@ impl Local {
         let prev_trust_count = db.unique_trust_proof_count();
 
         eprint!("Fetching {}... ", url);
-        match self.fetch_remote_git(url) {
+        match self.fetch_remote(url) {
             Ok(dir) => {
                 db.import_from_iter(proofs_iter_for_path(dir.clone()));
 
@@ -737,29 +1270,14 @@ impl Local {
                 continue;
             }
 
-            let repo = git2::Repository::open(&path);
-            if repo.is_err() {
-                continue;
-            }
-
-            let url = {
-                || -> Result<String> {
-                    let repo = repo.unwrap();
-                    let remote = repo.find_remote("origin")?;
-                    let url = remote
-                        .url()
-                        .ok_or_else(|| format_err!("origin has no url"))?;
-                    Ok(url.to_string())
-                }
-            }();
-
-            match url {
-                Ok(url) => {
+            match fetched_repo_url(&path) {
+                Ok(Some(url)) => {
                     if !fetched_urls.contains(&url) {
                         fetched_urls.insert(url.clone());
                         self.fetch_proof_repo_import_and_print_counts(&url, &mut db);
                     }
                 }
+                Ok(None) => {}
                 Err(e) => {
                     eprintln!("ERR: {} {}", path.display(), e);
                 }
@@ -771,6 +1289,255 @@ impl Local {
         Ok(())
     }
 
+    /// Proof repositories in the local cache, sorted by url
+    pub fn list_fetched_repos(&self) -> Result<Vec<FetchedProofRepo>> {
+        // read-only use of the index; saving it would forget the own proofs
+        let mut index = ProofIndex::open(&self.proof_index_path());
+        let mut repos = vec![];
+        if !self.cache_remotes_path().exists() {
+            return Ok(repos);
+        }
+        for entry in fs::read_dir(self.cache_remotes_path())? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let url = match fetched_repo_url(&path) {
+                Ok(Some(url)) => url,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("ERR: {} {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let proofs = index.proofs_in_dir(path.clone());
+            let last_commit = git2::Repository::open(&path)
+                .and_then(|repo| Ok(repo.head()?.peel_to_commit()?.time()))
+                .ok()
+                .map(|time| {
+                    chrono::FixedOffset::east(time.offset_minutes() * 60)
+                        .timestamp(time.seconds(), 0)
+                });
+            repos.push(FetchedProofRepo {
+                url,
+                author_ids: proofs
+                    .iter()
+                    .map(|proof| proof.content.author_id())
+                    .collect(),
+                proof_count: proofs.len(),
+                last_commit,
+                path,
+            });
+        }
+        repos.sort_by(|a, b| a.url.cmp(&b.url));
+        Ok(repos)
+    }
+
+    /// Check every proof of the fetched proof repository of `url`
+    pub fn verify_fetched_repo_integrity(&self, url: &str) -> Result<RepoIntegrity> {
+        let path = self.get_remote_git_cache_path(url)?;
+        if !path.exists() {
+            bail!("Proof repository {} was not fetched", url);
+        }
+        verify_repo_integrity(url.to_owned(), path)
+    }
+
+    /// Check every proof of all the fetched proof repositories, sorted by url
+    pub fn verify_all_fetched_repos_integrity(&self) -> Result<Vec<RepoIntegrity>> {
+        self.list_fetched_repos()?
+            .into_iter()
+            .map(|repo| verify_repo_integrity(repo.url, repo.path))
+            .collect()
+    }
+
+    /// Proofs added to the local proof repository, but not committed yet
+    pub fn get_uncommitted_proofs(&self) -> Result<Vec<proof::Proof>> {
+        let output = std::process::Command::new("git")
+            .arg("diff")
+            .arg("HEAD")
+            .arg("--unified=0")
+            .arg("--no-color")
+            .arg("--")
+            .arg("*.crev")
+            .current_dir(self.get_proofs_dir_path()?)
+            .output()?;
+        if !output.status.success() {
+            bail!(
+                "`git diff` failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        // proofs are only ever appended, so added lines make complete proofs
+        let mut added = String::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if line.starts_with('+') && !line.starts_with("+++") {
+                added.push_str(&line[1..]);
+                added.push('\n');
+            }
+        }
+        proof::Proof::parse(added.as_bytes())
+    }
+
+    /// Remove own trust proofs for `id` from the local proof repository
+    ///
+    /// Only proofs authored by the current Id are touched. Removed proofs
+    /// are returned along with whether they were already published, that is
+    /// found upstream of the proof repository. Nothing is changed if any of
+    /// the proofs can't be removed.
+    pub fn remove_own_trust_proofs_for(&self, id: &Id) -> Result<Vec<(proof::Proof, bool)>> {
+        let cur_id = self.get_current_userid()?;
+        let proof_dir = self.get_proofs_dir_path()?;
+        let repo = git2::Repository::open(&proof_dir)?;
+        let mut index = repo.index()?;
+
+        // check all the files before changing any
+        let mut changes = vec![];
+        for entry in walkdir::WalkDir::new(&proof_dir)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git")
+        {
+            let path = entry?.into_path();
+            if !path.is_file() || path.extension().map(|ext| ext != "crev").unwrap_or(true) {
+                continue;
+            }
+
+            let (to_remove, to_keep): (Vec<_>, Vec<_>) = proof::Proof::parse_from(&path)?
+                .into_iter()
+                .partition(|proof| match proof.content {
+                    proof::Content::Trust(ref trust) => {
+                        trust.from.id == cur_id && trust.ids.iter().any(|pub_id| pub_id.id == *id)
+                    }
+                    _ => false,
+                });
+            if to_remove.is_empty() {
+                continue;
+            }
+
+            let rel_path = path.strip_prefix(&proof_dir)?.to_owned();
+            for proof in &to_remove {
+                if let proof::Content::Trust(ref trust) = proof.content {
+                    if trust.ids.len() > 1 {
+                        bail!(
+                            "Trust proof in {} covers other Ids too; not removing it",
+                            rel_path.display()
+                        );
+                    }
+                }
+            }
+
+            changes.push((path, rel_path, to_remove, to_keep));
+        }
+
+        let mut removed = vec![];
+        for (path, rel_path, to_remove, to_keep) in changes {
+            let upstream = self.read_upstream_file(&repo, &rel_path);
+            for proof in to_remove {
+                let published = match upstream {
+                    Some(ref content) => content.contains(&proof.signature),
+                    None => false,
+                };
+                removed.push((proof, published));
+            }
+
+            if to_keep.is_empty() {
+                fs::remove_file(&path)?;
+                index.remove_path(&rel_path)?;
+            } else {
+                let mut content = String::new();
+                for proof in to_keep {
+                    content.push_str(&proof.to_string());
+                    content.push('\n');
+                }
+                crev_common::store_str_to_file(&path, &content)?;
+                index.add_path(&rel_path)?;
+            }
+        }
+        index.write()?;
+
+        Ok(removed)
+    }
+
+    /// The most recent proof of the current Id in the local proof repository
+    ///
+    /// Returned with the file it's in, and whether it was already published,
+    /// that is found upstream of the proof repository.
+    pub fn find_last_own_proof(&self) -> Result<Option<(PathBuf, proof::Proof, bool)>> {
+        let cur_id = self.get_current_userid()?;
+        let proof_dir = self.get_proofs_dir_path()?;
+        let repo = git2::Repository::open(&proof_dir)?;
+
+        let mut last: Option<(PathBuf, proof::Proof)> = None;
+        for path in proof_files_for_path(proof_dir.clone()) {
+            let path = path?;
+            for proof in proof::Proof::parse_from(&path)? {
+                if proof.content.author_id() != cur_id {
+                    continue;
+                }
+                let is_later = match last {
+                    Some((_, ref last_proof)) => proof.content.date() > last_proof.content.date(),
+                    None => true,
+                };
+                if is_later {
+                    last = Some((path.clone(), proof));
+                }
+            }
+        }
+
+        Ok(match last {
+            Some((path, proof)) => {
+                let rel_path = path.strip_prefix(&proof_dir)?.to_owned();
+                let published = match self.read_upstream_file(&repo, &rel_path) {
+                    Some(content) => content.contains(&proof.signature),
+                    None => false,
+                };
+                Some((path, proof, published))
+            }
+            None => None,
+        })
+    }
+
+    /// Replace `old` proof in the file at `path` with `new`, keeping the other proofs
+    pub fn replace_proof(&self, path: &Path, old: &proof::Proof, new: &proof::Proof) -> Result<()> {
+        let proof_dir = self.get_proofs_dir_path()?;
+        let repo = git2::Repository::open(&proof_dir)?;
+        let mut index = repo.index()?;
+
+        let proofs = proof::Proof::parse_from(path)?;
+        if !proofs.iter().any(|proof| proof.signature == old.signature) {
+            bail!("Proof not found in {}", path.display());
+        }
+        let mut file = fs::File::create(path)?;
+        for proof in proofs {
+            let proof = if proof.signature == old.signature {
+                new
+            } else {
+                &proof
+            };
+            file.write_all(proof.to_string().as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()?;
+        index.add_path(path.strip_prefix(&proof_dir)?)?;
+        index.write()?;
+
+        Ok(())
+    }
+
+    /// Content of a file in the upstream branch of the proof repository, if any
+    fn read_upstream_file(&self, repo: &git2::Repository, rel_path: &Path) -> Option<String> {
+        let head = git2::Branch::wrap(repo.head().ok()?);
+        let tree = head.upstream().ok()?.get().peel_to_tree().ok()?;
+        let blob = tree
+            .get_path(rel_path)
+            .ok()?
+            .to_object(repo)
+            .ok()?
+            .peel_to_blob()
+            .ok()?;
+        Some(String::from_utf8_lossy(blob.content()).into_owned())
+    }
+
     pub fn run_git(&self, args: Vec<OsString>) -> Result<std::process::ExitStatus> {
         let orig_dir = std::env::current_dir()?;
         let proof_dir_path = self.get_proofs_dir_path()?;
@@ -807,6 +1574,21 @@ impl Local {
         Ok(())
     }
 
+    pub fn get_user_config_value(&self, key: &str) -> Result<Option<String>> {
+        self.load_user_config()?.get_value(key)
+    }
+
+    pub fn set_user_config_value(&self, key: &str, value: &str) -> Result<()> {
+        if key == "current-id" && !value.is_empty() {
+            // make sure the Id exists locally
+            return self.save_current_id(&Id::crevid_from_str(value)?);
+        }
+        let mut config = self.load_user_config()?;
+        config.set_value(key, value)?;
+        self.store_user_config(&config)?;
+        Ok(())
+    }
+
     pub fn store_config_open_cmd(&self, cmd: String) -> Result<()> {
         let mut config = self.load_user_config()?;
         config.open_cmd = Some(cmd);
@@ -817,13 +1599,33 @@ impl Local {
     /// Create a new proofdb, and populate it with local repo
     /// and cache content.
     pub fn load_db(&self) -> Result<crate::ProofDB> {
+        let mut index = ProofIndex::open(&self.proof_index_path());
         let mut db = crate::ProofDB::new();
-        db.import_from_iter(self.proofs_iter()?);
-        db.import_from_iter(proofs_iter_for_path(self.cache_remotes_path()));
+        if let Some(dir) = self.get_proofs_dir_path_opt()? {
+            db.import_from_iter(index.proofs_in_dir(dir).into_iter());
+        }
+        db.import_from_iter(index.proofs_in_dir(self.cache_remotes_path()).into_iter());
+        // the index only saves time, so failing to write it isn't an error
+        if let Err(e) = index.save() {
+            eprintln!("Failed to save the proof index: {}", e);
+        }
 
         Ok(db)
     }
 
+    fn proof_index_path(&self) -> PathBuf {
+        self.cache_path.join("proof-index")
+    }
+
+    /// Remove the proof index, so the next `load_db` reads all proofs again
+    pub fn remove_proof_index(&self) -> Result<()> {
+        let path = self.proof_index_path();
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
     pub fn proof_dir_git_add_path(&self, rel_path: &Path) -> Result<()> {
         let proof_dir = self.get_proofs_dir_path()?;
         let repo = git2::Repository::open(&proof_dir)?;
@@ -869,6 +1671,7 @@ impl Local {
         url: Option<String>,
         github_username: Option<String>,
         use_https_push: bool,
+        seed: Option<&[u8]>,
     ) -> Result<()> {
         let url = match (url, github_username) {
             (Some(url), None) => url,
@@ -883,7 +1686,17 @@ impl Local {
 
         self.clone_proof_dir_from_git(&url, use_https_push)?;
 
-        let id = crev_data::id::OwnId::generate(crev_data::Url::new_git(url.clone()));
+        let id = match seed {
+            Some(seed) => {
+                eprintln!("WARNING: The CrevID is generated from the given seed.");
+                eprintln!(
+                    "WARNING: Anyone knowing the seed can recreate it and sign proofs as you."
+                );
+                eprintln!("WARNING: Don't use it for anything but tests.");
+                crev_data::id::OwnId::generate_from_seed(crev_data::Url::new_git(url.clone()), seed)
+            }
+            None => crev_data::id::OwnId::generate(crev_data::Url::new_git(url.clone())),
+        };
         eprintln!("CrevID will be protected by a passphrase.");
         eprintln!("There's no way to recover your CrevID if you forget your passphrase.");
         let passphrase = crev_common::read_new_passphrase()?;
@@ -932,19 +1745,35 @@ impl Local {
         Ok(())
     }
 
-    pub fn export_locked_id(&self, id_str: Option<String>) -> Result<String> {
-        let id = if let Some(id_str) = id_str {
+    /// `LockedId` of `id_str`, or the current one
+    fn read_locked_id_or_current(&self, id_str: Option<String>) -> Result<LockedId> {
+        if let Some(id_str) = id_str {
             let id = Id::crevid_from_str(&id_str)?;
-            self.read_locked_id(&id)?
+            self.read_locked_id(&id)
         } else {
-            self.read_current_locked_id()?
-        };
+            self.read_current_locked_id()
+        }
+    }
+
+    /// Export a `LockedId`, optionally armored (see `LockedId::to_armored_string`)
+    pub fn export_locked_id(&self, id_str: Option<String>, armor: bool) -> Result<String> {
+        let id = self.read_locked_id_or_current(id_str)?;
 
-        Ok(id.to_string())
+        if armor {
+            id.to_armored_string()
+        } else {
+            Ok(id.to_string())
+        }
     }
 
+    /// Public part of an own Id (default: current Id), safe to share
+    pub fn export_pub_id(&self, id_str: Option<String>) -> Result<PubId> {
+        Ok(self.read_locked_id_or_current(id_str)?.to_pubid())
+    }
+
+    /// Import a `LockedId`, exported with or without armor
     pub fn import_locked_id(&self, locked_id_serialized: &str) -> Result<PubId> {
-        let id = LockedId::from_str(locked_id_serialized)?;
+        let id = LockedId::from_str_maybe_armored(locked_id_serialized)?;
         self.save_locked_id(&id)?;
         Ok(id.to_pubid())
     }
@@ -989,9 +1818,92 @@ impl ProofStore for Local {
     }
 }
 
-fn proofs_iter_for_path(path: PathBuf) -> impl Iterator<Item = proof::Proof> {
+/// File recording the url of proofs fetched over plain HTTP(S)
+const HTTP_REMOTE_URL_FILE: &str = ".crev-http-url";
+
+/// Url a proof repository in the cache was fetched from
+///
+/// `None` if the directory is neither a git repository, nor fetched over http.
+fn fetched_repo_url(path: &Path) -> Result<Option<String>> {
+    match git2::Repository::open(path) {
+        Ok(repo) => {
+            let remote = repo.find_remote("origin")?;
+            let url = remote
+                .url()
+                .ok_or_else(|| format_err!("origin has no url"))?;
+            Ok(Some(url.to_string()))
+        }
+        Err(_) => Ok(fs::read_to_string(path.join(HTTP_REMOTE_URL_FILE)).ok()),
+    }
+}
+
+/// A proof repository fetched into the local cache
+#[derive(Clone, Debug)]
+pub struct FetchedProofRepo {
+    pub url: String,
+    pub path: PathBuf,
+    /// Ids that signed the proofs in the repository
+    pub author_ids: BTreeSet<Id>,
+    pub proof_count: usize,
+    /// Date of the `HEAD` commit; `None` for repositories fetched over http
+    pub last_commit: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+/// Proofs of a proof repository in the cache that can't be trusted
+#[derive(Clone, Debug)]
+pub struct RepoIntegrity {
+    pub url: String,
+    pub path: PathBuf,
+    /// Number of proofs with a valid signature
+    pub valid_count: usize,
+    /// Proof files with proofs that failed to parse or verify, with the reason
+    pub broken: Vec<(PathBuf, String)>,
+}
+
+fn verify_repo_integrity(url: String, path: PathBuf) -> Result<RepoIntegrity> {
+    let mut valid_count = 0;
+    let mut broken = vec![];
+    for file in proof_files_for_path(path.clone()) {
+        let file = file?;
+        let proofs = match fs::File::open(&file)
+            .map_err(Into::into)
+            .and_then(|f| proof::Proof::parse_each(io::BufReader::new(f)))
+        {
+            Ok(proofs) => proofs,
+            Err(e) => {
+                broken.push((file, format!("can't parse: {}", e)));
+                continue;
+            }
+        };
+        for (i, proof) in proofs.into_iter().enumerate() {
+            match proof.and_then(|proof| proof.verify()) {
+                Ok(()) => valid_count += 1,
+                Err(e) => broken.push((file.clone(), format!("proof {}: {}", i + 1, e))),
+            }
+        }
+    }
+    Ok(RepoIntegrity {
+        url,
+        path,
+        valid_count,
+        broken,
+    })
+}
+
+fn is_archive_url(url: &str) -> bool {
+    url.ends_with(".tar.gz") || url.ends_with(".tgz")
+}
+
+/// Urls of proofs published over plain HTTP(S) instead of a git repository
+pub fn is_http_proofs_url(url: &str) -> bool {
+    (url.starts_with("https://") || url.starts_with("http://"))
+        && (url.ends_with(".crev") || is_archive_url(url))
+}
+
+/// All the `.crev` files under `path`
+pub(crate) fn proof_files_for_path(path: PathBuf) -> impl Iterator<Item = Result<PathBuf>> {
     use std::ffi::OsStr;
-    let file_iter = walkdir::WalkDir::new(path)
+    walkdir::WalkDir::new(path)
         .into_iter()
         .map_err(|e| format_err!("Error iterating local ProofStore: {:?}", e))
         .filter_map_ok(|entry| {
@@ -1005,9 +1917,11 @@ fn proofs_iter_for_path(path: PathBuf) -> impl Iterator<Item = proof::Proof> {
                 Some(osext) if osext == osext_match => Some(path.to_owned()),
                 _ => None,
             }
-        });
+        })
+}
 
-    let proofs_iter = file_iter
+fn proofs_iter_for_path(path: PathBuf) -> impl Iterator<Item = proof::Proof> {
+    let proofs_iter = proof_files_for_path(path)
         .and_then_ok(|path| Ok(proof::Proof::parse_from(&path)?))
         .flatten_ok()
         .and_then_ok(|proof| {
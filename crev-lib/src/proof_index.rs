@@ -0,0 +1,166 @@
+//! Proofs read from proof files, kept on disk so that loading the `ProofDB`
+//! only parses and verifies the files that changed since
+use crate::{local::proof_files_for_path, prelude::*};
+use crev_data::proof;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Bump on changes to the format of the index
+const PROOF_INDEX_VERSION: i64 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct IndexedFile {
+    modified: SystemTime,
+    len: u64,
+    proofs: Vec<proof::Proof>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexData {
+    version: i64,
+    // proofs are stored parsed, so another version of crev might read them differently
+    crev_version: String,
+    files: HashMap<PathBuf, IndexedFile>,
+}
+
+pub struct ProofIndex {
+    path: PathBuf,
+    files: HashMap<PathBuf, IndexedFile>,
+    seen: HashSet<PathBuf>,
+    changed: bool,
+}
+
+impl ProofIndex {
+    /// Open the index stored at `path`
+    ///
+    /// A missing, corrupted or outdated index is silently started from scratch.
+    pub fn open(path: &Path) -> Self {
+        let files = fs::File::open(path)
+            .ok()
+            .and_then(|file| serde_cbor::from_reader::<IndexData, _>(io::BufReader::new(file)).ok())
+            .filter(|data| {
+                data.version == PROOF_INDEX_VERSION
+                    && data.crev_version == env!("CARGO_PKG_VERSION")
+            })
+            .map(|data| data.files)
+            .unwrap_or_default();
+
+        Self {
+            path: path.to_owned(),
+            files,
+            seen: HashSet::new(),
+            changed: false,
+        }
+    }
+
+    /// Valid proofs from all the proof files under `dir`
+    ///
+    /// Only files that changed since they were indexed are read again.
+    /// Files with invalid proofs aren't indexed, so the errors are reported every time.
+    pub fn proofs_in_dir(&mut self, dir: PathBuf) -> Vec<proof::Proof> {
+        let mut proofs = vec![];
+        for file_path in proof_files_for_path(dir) {
+            let file_path = match file_path {
+                Ok(file_path) => file_path,
+                Err(e) => {
+                    eprintln!("Failed processing a proof: {}", e);
+                    continue;
+                }
+            };
+            self.seen.insert(file_path.clone());
+
+            let stamp = fs::metadata(&file_path)
+                .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                .ok();
+            match (self.files.get(&file_path), stamp) {
+                (Some(indexed), Some((modified, len)))
+                    if indexed.modified == modified && indexed.len == len =>
+                {
+                    proofs.extend(indexed.proofs.iter().cloned());
+                    continue;
+                }
+                _ => {}
+            }
+
+            let (file_proofs, all_valid) = read_valid_proofs(&file_path);
+            match stamp {
+                Some((modified, len)) if all_valid => {
+                    self.files.insert(
+                        file_path,
+                        IndexedFile {
+                            modified,
+                            len,
+                            proofs: file_proofs.clone(),
+                        },
+                    );
+                    self.changed = true;
+                }
+                _ => {
+                    if self.files.remove(&file_path).is_some() {
+                        self.changed = true;
+                    }
+                }
+            }
+            proofs.extend(file_proofs);
+        }
+        proofs
+    }
+
+    /// Forget the files that are gone, and write the index if it changed
+    pub fn save(mut self) -> Result<()> {
+        let seen = &self.seen;
+        let indexed_count = self.files.len();
+        self.files.retain(|path, _| seen.contains(path));
+        if !self.changed && indexed_count == self.files.len() {
+            return Ok(());
+        }
+
+        let data = IndexData {
+            version: PROOF_INDEX_VERSION,
+            crev_version: env!("CARGO_PKG_VERSION").to_owned(),
+            files: self.files,
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = self.path.with_extension("tmp");
+        let mut file = io::BufWriter::new(fs::File::create(&tmp_path)?);
+        serde_cbor::to_writer(&mut file, &data)?;
+        file.flush()?;
+        drop(file);
+        fs::rename(tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Parse and verify the proofs in a file, reporting the invalid ones
+///
+/// Also returns whether all of them were valid.
+fn read_valid_proofs(path: &Path) -> (Vec<proof::Proof>, bool) {
+    let parsed = match proof::Proof::parse_from(path) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Failed processing a proof: {}", e);
+            return (vec![], false);
+        }
+    };
+
+    let mut all_valid = true;
+    let mut proofs = vec![];
+    for proof in parsed {
+        match proof.verify() {
+            Ok(()) => proofs.push(proof),
+            Err(e) => {
+                eprintln!("Failed processing a proof: {}", e);
+                all_valid = false;
+            }
+        }
+    }
+    (proofs, all_valid)
+}
@@ -12,6 +12,7 @@ use crev_data::{
 };
 use default::default;
 use semver::Version;
+use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 /// A `T` with a timestamp
@@ -160,6 +161,24 @@ impl Default for ProofDB {
     }
 }
 
+/// Summary of what's in a `ProofDB`
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ProofDBStats {
+    /// Ids with a known proof repository url
+    pub ids: usize,
+    /// Distinct proof repository urls
+    pub repos: usize,
+    pub package_reviews: usize,
+    pub trust_proofs: usize,
+    pub advisories: usize,
+    pub issues: usize,
+    /// Distinct crates (source and name) with at least one review
+    pub reviewed_crates: usize,
+    /// Distinct crate versions with at least one review
+    pub reviewed_crate_versions: usize,
+}
+
 #[derive(Default, Debug)]
 pub struct IssueDetails {
     pub severity: Level,
@@ -169,6 +188,39 @@ pub struct IssueDetails {
     pub advisories: HashSet<PkgReviewId>,
 }
 
+/// How a review of a digest is taken into account when verifying it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReviewVerdict {
+    /// Counts towards the verification
+    Counts,
+    /// Negative review from a trusted reviewer, failing the verification
+    Negative,
+    /// Created before `VerificationRequirements::since`
+    TooOld,
+    /// Reviewer not in the trust set
+    NotTrusted,
+    /// Reviewer trusted less than `VerificationRequirements::trust_level`
+    TrustTooLow,
+    /// Review without any rating
+    NoRating,
+    /// Thoroughness or understanding below the requirements
+    TooShallow,
+}
+
+impl ReviewVerdict {
+    pub fn description(self) -> &'static str {
+        match self {
+            ReviewVerdict::Counts => "counts",
+            ReviewVerdict::Negative => "counts as negative",
+            ReviewVerdict::TooOld => "doesn't count: older than `--since`",
+            ReviewVerdict::NotTrusted => "doesn't count: reviewer not in the trust set",
+            ReviewVerdict::TrustTooLow => "doesn't count: reviewer trust too low",
+            ReviewVerdict::NoRating => "doesn't count: review has no rating",
+            ReviewVerdict::TooShallow => "doesn't count: thoroughness or understanding too low",
+        }
+    }
+}
+
 impl ProofDB {
     pub fn new() -> Self {
         default()
@@ -544,6 +596,37 @@ impl ProofDB {
             .fold(0, |count, (_id, set)| count + set.len())
     }
 
+    /// Count what's known, for an overview of the data trust decisions rest on
+    pub fn stats(&self) -> ProofDBStats {
+        let latest_reviews = || {
+            self.package_review_signatures_by_pkg_review_id
+                .values()
+                .map(move |signature| &self.package_review_by_signature[&signature.value])
+        };
+
+        ProofDBStats {
+            ids: self.all_known_ids().len(),
+            repos: self
+                .url_by_id
+                .values()
+                .chain(self.url_by_id_secondary.values())
+                .map(|url| &url.value.url)
+                .collect::<HashSet<_>>()
+                .len(),
+            package_reviews: self.unique_package_review_proof_count(),
+            trust_proofs: self.unique_trust_proof_count(),
+            advisories: latest_reviews().map(|review| review.advisories.len()).sum(),
+            issues: latest_reviews().map(|review| review.issues.len()).sum(),
+            reviewed_crates: self.package_reviews.values().map(BTreeMap::len).sum(),
+            reviewed_crate_versions: self
+                .package_reviews
+                .values()
+                .flat_map(BTreeMap::values)
+                .map(BTreeMap::len)
+                .sum(),
+        }
+    }
+
     fn add_code_review(&mut self, review: &review::Code) {
         let from = &review.from;
         self.record_url_from_from_field(&review.date_utc(), &from);
@@ -649,6 +732,13 @@ impl ProofDB {
         }
     }
 
+    /// All the (most recent) trust relationships: `(from, to, level)`
+    pub fn all_trust_edges(&self) -> impl Iterator<Item = (&Id, &Id, TrustLevel)> {
+        self.trust_id_to_id
+            .iter()
+            .flat_map(|(from, map)| map.iter().map(move |(to, trust)| (from, to, trust.value)))
+    }
+
     pub fn all_known_ids(&self) -> BTreeSet<Id> {
         self.url_by_id
             .keys()
@@ -694,38 +784,82 @@ impl ProofDB {
             })
     }
 
-    pub fn verify_package_digest(
+    /// Reviews of `digest`, one per reviewer sorted by reviewer Id, and how each
+    /// is taken into account by `verify_package_digest`
+    pub fn get_package_review_verdicts(
         &self,
         digest: &Digest,
         trust_set: &TrustSet,
         requirements: &VerificationRequirements,
-    ) -> VerificationStatus {
-        let reviews: HashMap<Id, review::Package> = self
+    ) -> Vec<(review::Package, ReviewVerdict)> {
+        let reviews: BTreeMap<Id, review::Package> = self
             .get_package_reviews_by_digest(digest)
             .map(|review| (review.from.id.clone(), review))
             .collect();
-        // Faster somehow maybe?
-        let reviews_by: HashSet<Id, _> = reviews.keys().cloned().collect();
-        let trusted_ids: HashSet<_> = trust_set.trusted_ids().cloned().collect();
-        let matching_reviewers = trusted_ids.intersection(&reviews_by);
-        let mut trust_count = 0;
-        let mut negative_count = 0;
-        for matching_reviewer in matching_reviewers {
-            let review = &reviews[matching_reviewer].review;
-            if !review.is_none()
-                && Rating::Neutral <= review.rating
-                && requirements.thoroughness <= review.thoroughness
-                && requirements.understanding <= review.understanding
-            {
-                if TrustLevel::from(requirements.trust_level)
-                    <= trust_set.get_effective_trust_level(matching_reviewer)
-                {
-                    trust_count += 1;
-                }
-            } else if review.rating <= Rating::Negative {
-                negative_count += 1;
-            }
-        }
+        let min_trust_level = TrustLevel::from(requirements.trust_level);
+        reviews
+            .into_iter()
+            .map(|(id, pkg_review)| {
+                let review = &pkg_review.review;
+                let too_old = match requirements.since {
+                    Some(ref since) => pkg_review.date() < since,
+                    None => false,
+                };
+                let verdict = match trust_set.get_effective_trust_level_opt(&id) {
+                    _ if too_old => ReviewVerdict::TooOld,
+                    None => ReviewVerdict::NotTrusted,
+                    Some(trust_level)
+                        if !review.is_none()
+                            && Rating::Neutral <= review.rating
+                            && requirements.thoroughness <= review.thoroughness
+                            && requirements.understanding <= review.understanding =>
+                    {
+                        if min_trust_level <= trust_level {
+                            ReviewVerdict::Counts
+                        } else {
+                            ReviewVerdict::TrustTooLow
+                        }
+                    }
+                    Some(_) if review.rating <= Rating::Negative => ReviewVerdict::Negative,
+                    Some(_) if review.is_none() => ReviewVerdict::NoRating,
+                    Some(_) => ReviewVerdict::TooShallow,
+                };
+                (pkg_review, verdict)
+            })
+            .collect()
+    }
+
+    /// Reviewers whose reviews of `digest` count towards verifying it, treating Ids
+    /// publishing to the same proof repository as one
+    pub fn count_independent_reviewers(
+        &self,
+        digest: &Digest,
+        trust_set: &TrustSet,
+        requirements: &VerificationRequirements,
+    ) -> u64 {
+        self.get_package_review_verdicts(digest, trust_set, requirements)
+            .into_iter()
+            .filter(|(_, verdict)| *verdict == ReviewVerdict::Counts)
+            .map(|(review, _)| review.from.url.url)
+            .collect::<HashSet<_>>()
+            .len() as u64
+    }
+
+    pub fn verify_package_digest(
+        &self,
+        digest: &Digest,
+        trust_set: &TrustSet,
+        requirements: &VerificationRequirements,
+    ) -> VerificationStatus {
+        let verdicts = self.get_package_review_verdicts(digest, trust_set, requirements);
+        let count = |expected| {
+            verdicts
+                .iter()
+                .filter(|(_, verdict)| *verdict == expected)
+                .count() as u64
+        };
+        let trust_count = count(ReviewVerdict::Counts);
+        let negative_count = count(ReviewVerdict::Negative);
 
         if negative_count > 0 {
             VerificationStatus::Negative
@@ -794,6 +928,11 @@ impl ProofDB {
         }
     }
 
+    /// Add a trust proof that isn't signed (yet), eg. to see what it would change
+    pub fn import_unsigned_trust(&mut self, trust: &proof::Trust) {
+        self.add_trust(trust);
+    }
+
     fn get_trust_list_of_id(&self, id: &Id) -> impl Iterator<Item = (TrustLevel, &Id)> {
         if let Some(map) = self.trust_id_to_id.get(id) {
             Some(map.iter().map(|(id, trust)| (trust.value, id)))
@@ -903,6 +1042,142 @@ impl ProofDB {
         visited
     }
 
+    /// Find trust cycles (eg. A trusts B, B trusts A) between Ids in `trust_set`
+    ///
+    /// Cycles are not a problem for the trust calculation and don't affect it,
+    /// but they might help explain unexpected effective trust levels.
+    /// Every cycle found is returned once, starting with its smallest Id.
+    pub fn find_trust_cycles(&self, trust_set: &TrustSet) -> Vec<Vec<Id>> {
+        let trusted_by = |id: &Id| -> std::vec::IntoIter<&Id> {
+            let mut ids: Vec<_> = self
+                .get_trust_list_of_id(id)
+                .filter(|(level, to)| *level > TrustLevel::None && trust_set.contains_trusted(to))
+                .map(|(_, to)| to)
+                .collect();
+            ids.sort();
+            ids.into_iter()
+        };
+
+        let mut cycles = BTreeSet::new();
+        let mut finished = HashSet::new();
+        let mut roots: Vec<_> = trust_set.trusted_ids().collect();
+        roots.sort();
+
+        for root in roots {
+            if finished.contains(root) {
+                continue;
+            }
+            let mut path = vec![root];
+            let mut pending = vec![trusted_by(root)];
+
+            while let Some(next) = pending.last_mut().map(Iterator::next) {
+                match next {
+                    Some(next) => {
+                        if let Some(pos) = path.iter().position(|id| *id == next) {
+                            let mut cycle: Vec<Id> =
+                                path[pos..].iter().map(|id| (*id).clone()).collect();
+                            let min_pos = (0..cycle.len())
+                                .min_by_key(|&i| &cycle[i])
+                                .expect("cycle not empty");
+                            cycle.rotate_left(min_pos);
+                            cycles.insert(cycle);
+                        } else if !finished.contains(next) {
+                            path.push(next);
+                            pending.push(trusted_by(next));
+                        }
+                    }
+                    None => {
+                        pending.pop();
+                        finished.insert(path.pop().expect("path not empty"));
+                    }
+                }
+            }
+        }
+
+        cycles.into_iter().collect()
+    }
+
+    /// Find the chains of trust proofs that make `to_id` trusted in `trust_set`
+    ///
+    /// Mirrors `calculate_trust_set`: only Ids of `trust_set` that are not distrusted,
+    /// and paths within `params`, are followed. Returns at most `max_paths` paths,
+    /// best first - unless some were cut off, the first one determines
+    /// the effective trust level.
+    pub fn find_trust_paths(
+        &self,
+        trust_set: &TrustSet,
+        for_id: &Id,
+        to_id: &Id,
+        params: &TrustDistanceParams,
+        max_paths: usize,
+    ) -> Vec<TrustPath> {
+        let mut paths = vec![];
+        if !trust_set.contains_trusted(to_id) {
+            return paths;
+        }
+
+        let edges_of = |id: &Id| -> std::vec::IntoIter<(TrustLevel, &Id)> {
+            let mut edges: Vec<_> = self
+                .get_trust_list_of_id(id)
+                .filter(|(_, to)| {
+                    trust_set.contains_trusted(to) && !trust_set.contains_distrusted(to)
+                })
+                .collect();
+            edges.sort_by(|a, b| a.1.cmp(b.1));
+            edges.into_iter()
+        };
+
+        // depth-first over simple paths; `steps` and `pending` grow and shrink together
+        let mut steps: Vec<(Id, TrustLevel)> = vec![];
+        let mut distances = vec![0];
+        let mut pending = vec![edges_of(for_id)];
+        while let Some(next) = pending.last_mut().map(Iterator::next) {
+            let (level, next_id) = match next {
+                Some(next) => next,
+                None => {
+                    pending.pop();
+                    steps.pop();
+                    distances.pop();
+                    continue;
+                }
+            };
+            let distance = match params.distance_by_level(level) {
+                Some(d) => distances.last().expect("not empty") + d,
+                None => continue,
+            };
+            if distance > params.max_distance
+                || next_id == for_id
+                || steps.iter().any(|(id, _)| id == next_id)
+            {
+                continue;
+            }
+
+            if next_id == to_id {
+                let mut path_steps = steps.clone();
+                path_steps.push((next_id.clone(), level));
+                paths.push(TrustPath {
+                    steps: path_steps,
+                    distance,
+                });
+                if paths.len() >= max_paths {
+                    break;
+                }
+                continue;
+            }
+
+            steps.push((next_id.clone(), level));
+            distances.push(distance);
+            pending.push(edges_of(next_id));
+        }
+
+        paths.sort_by(|a, b| {
+            b.effective_trust()
+                .cmp(&a.effective_trust())
+                .then(a.distance.cmp(&b.distance))
+        });
+        paths
+    }
+
     pub fn lookup_url(&self, id: &Id) -> Option<&Url> {
         self.url_by_id
             .get(id)
@@ -911,6 +1186,25 @@ impl ProofDB {
     }
 }
 
+/// A chain of trust proofs from the root of a WoT to an Id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustPath {
+    /// Ids after the root, each with the trust level the previous one gave it
+    pub steps: Vec<(Id, TrustLevel)>,
+    pub distance: u64,
+}
+
+impl TrustPath {
+    /// The lowest trust level along the path
+    pub fn effective_trust(&self) -> TrustLevel {
+        self.steps
+            .iter()
+            .map(|(_, level)| *level)
+            .min()
+            .unwrap_or(TrustLevel::High)
+    }
+}
+
 /// Details of a one Id that is
 #[derive(Debug, Clone)]
 struct TrustedIdDetails {
@@ -939,6 +1233,21 @@ impl TrustSet {
         self.distrusted.contains_key(id)
     }
 
+    /// Ids whose distrust proofs excluded `id` from the set
+    pub fn distrusted_by(&self, id: &Id) -> impl Iterator<Item = &Id> {
+        self.distrusted.get(id).into_iter().flatten()
+    }
+
+    /// Ids whose trust proofs brought `id` into the set, each with the trust level it passed on
+    pub fn trusted_by<'a>(&'a self, id: &'a Id) -> impl Iterator<Item = (&'a Id, TrustLevel)> + 'a {
+        self.trusted
+            .get(id)
+            .into_iter()
+            .flat_map(|details| details.referers.iter())
+            .filter(move |(referer, _)| *referer != id)
+            .map(|(referer, level)| (referer, *level))
+    }
+
     /// Record that an Id is considered trusted
     ///
     /// Returns `true` if this actually added or changed the `subject` details,
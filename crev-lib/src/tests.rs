@@ -7,6 +7,25 @@ use std::str::FromStr;
 
 mod issues;
 
+/// `Local` in a new temporary directory, removed when the returned `TempDir` is dropped
+///
+/// Not using `CREV_ROOT_DIR`, as tests run in parallel.
+fn temp_local() -> Result<(tempdir::TempDir, Local)> {
+    let root = tempdir::TempDir::new("crev-root")?;
+    let local = Local::create_or_open_at(root.path())?;
+    Ok((root, local))
+}
+
+/// `temp_local` with a current Id locked with `passphrase`, and a git proof repo
+fn temp_local_with_id(passphrase: &str) -> Result<(tempdir::TempDir, Local, OwnId)> {
+    let (root, local) = temp_local()?;
+    let id = OwnId::generate_for_git_url("https://example.com/crev-proofs");
+    local.save_locked_id(&id::LockedId::from_own_id(&id, passphrase)?)?;
+    local.save_current_id(&id.id.id)?;
+    git2::Repository::init(local.get_proofs_dir_path()?)?;
+    Ok((root, local, id))
+}
+
 // Basic liftime of an `LockedId`:
 //
 // * generate
@@ -61,6 +80,486 @@ pass:
     Ok(())
 }
 
+#[test]
+fn armored_locked_id_roundtrip() -> Result<()> {
+    let id = OwnId::generate_for_git_url("https://example.com/crev-proofs");
+    let locked = id::LockedId::from_own_id(&id, "pass")?;
+
+    let armored = locked.to_armored_string()?;
+    assert!(armored.starts_with("-----BEGIN CREV LOCKED ID-----"));
+
+    // whitespace mangled by copy-pasting, and surrounded by other text
+    let mangled = format!(
+        "here it is: {}\nthanks",
+        armored.replace('\n', "\n   ").replace("  ", " ")
+    );
+    let restored = id::LockedId::from_str_maybe_armored(&mangled)?;
+    assert_eq!(restored.to_unlocked("pass")?.id.id, id.id.id);
+
+    // plain format still works
+    let restored = id::LockedId::from_str_maybe_armored(&locked.to_string())?;
+    assert_eq!(restored.to_unlocked("pass")?.id.id, id.id.id);
+
+    Ok(())
+}
+
+#[test]
+fn armored_locked_id_corrupted() -> Result<()> {
+    let id = OwnId::generate_for_git_url("https://example.com/crev-proofs");
+    let armored = id::LockedId::from_own_id(&id, "pass")?.to_armored_string()?;
+
+    let mut lines: Vec<String> = armored.lines().map(ToOwned::to_owned).collect();
+    let line = lines[3].clone();
+    let first = if line.starts_with('A') { "B" } else { "A" };
+    lines[3] = format!("{}{}", first, &line[1..]);
+    let corrupted = lines.join("\n");
+    assert!(id::LockedId::from_str_maybe_armored(&corrupted).is_err());
+
+    let truncated = armored.replace("-----END CREV LOCKED ID-----", "");
+    assert!(id::LockedId::from_str_maybe_armored(&truncated).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn user_config_verify_section() -> Result<()> {
+    let yaml = r#"
+---
+version: -1
+current-id: ~
+host-salt: 9jeCQhM2dMZErCErRQ_RmZ08X68xpta1tIhTbCHOTs0
+"#;
+    let config: local::UserConfig = serde_yaml::from_str(yaml)?;
+    assert!(config.verify.is_empty());
+
+    let yaml = format!(
+        "{}verify:\n  recursive: true\n  skip-indirect: false\n",
+        yaml
+    );
+    let config: local::UserConfig = serde_yaml::from_str(&yaml)?;
+    assert_eq!(config.verify.recursive, Some(true));
+    assert_eq!(config.verify.skip_indirect, Some(false));
+
+    let project = local::VerifyConfig {
+        skip_indirect: Some(true),
+        verbose: Some(false),
+        ..default()
+    };
+    let merged = project.or(config.verify);
+    assert_eq!(merged.skip_indirect, Some(true));
+    assert_eq!(merged.recursive, Some(true));
+    assert_eq!(merged.verbose, Some(false));
+    assert_eq!(merged.skip_verified, None);
+
+    Ok(())
+}
+
+#[test]
+fn verify_config_profiles() -> Result<()> {
+    let user: local::VerifyConfig = serde_yaml::from_str(
+        "recursive: true\nprofiles:\n  strict:\n    skip-verified: false\n    verbose: true\n",
+    )?;
+    let project: local::VerifyConfig = serde_yaml::from_str(
+        "skip-indirect: true\nprofiles:\n  strict:\n    skip-verified: true\n",
+    )?;
+
+    let strict = project.or(user).with_profile("strict")?;
+    assert_eq!(strict.skip_verified, Some(true));
+    assert_eq!(strict.verbose, Some(true));
+    assert_eq!(strict.recursive, Some(true));
+    assert_eq!(strict.skip_indirect, Some(true));
+
+    assert!(local::VerifyConfig::default()
+        .with_profile("strict")
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn user_config_get_set_value() -> Result<()> {
+    let mut config = local::UserConfig::default();
+    assert_eq!(config.get_value("open-cmd")?, None);
+
+    config.set_value("open-cmd", "xdg-open")?;
+    config.set_value("verify.skip-indirect", "true")?;
+    assert_eq!(config.get_value("open-cmd")?, Some("xdg-open".into()));
+    assert_eq!(config.verify.skip_indirect, Some(true));
+    assert_eq!(
+        config.get_value("verify.skip-indirect")?,
+        Some("true".into())
+    );
+
+    config.set_value("verify.skip-indirect", "")?;
+    assert_eq!(config.verify.skip_indirect, None);
+
+    config.set_value("crates-io-api", "https://mirror.example.com/api/v1")?;
+    assert_eq!(
+        config.crates_io_api,
+        Some("https://mirror.example.com/api/v1".into())
+    );
+
+    config.set_value("review-template", "/home/user/review-template.md")?;
+    assert_eq!(
+        config.get_value("review-template")?,
+        Some("/home/user/review-template.md".into())
+    );
+
+    config.set_value("diff-cmd", "difft")?;
+    assert_eq!(config.diff_cmd, Some("difft".into()));
+
+    config.set_value("post-verify-hook", "notify-send verified")?;
+    assert_eq!(
+        config.get_value("post-verify-hook")?,
+        Some("notify-send verified".into())
+    );
+
+    config.set_value("verify.trust", "medium")?;
+    config.set_value("verify.jobs", "2")?;
+    config.set_value("verify.columns", "owners,review-date")?;
+    assert_eq!(config.verify.trust, Some(crev_data::Level::Medium));
+    assert_eq!(config.verify.jobs, Some(2));
+    assert_eq!(
+        config.get_value("verify.columns")?,
+        Some("owners,review-date".into())
+    );
+    assert!(config.set_value("verify.redundancy", "many").is_err());
+
+    assert!(config.set_value("verify.post-verify-hook", "x").is_err());
+    assert!(config.set_value("verify.recursive", "yes").is_err());
+    assert!(config.set_value("no-such-key", "x").is_err());
+    assert!(config.get_value("verify.no-such-key").is_err());
+    assert!(config.get_value("host-salt").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn http_proofs_url_detection() {
+    assert!(local::is_http_proofs_url("https://example.com/proofs.crev"));
+    assert!(local::is_http_proofs_url(
+        "http://example.com/crev-proofs.tar.gz"
+    ));
+    assert!(local::is_http_proofs_url(
+        "https://example.com/crev-proofs.tgz"
+    ));
+    assert!(!local::is_http_proofs_url(
+        "https://github.com/dpc/crev-proofs"
+    ));
+    assert!(!local::is_http_proofs_url("file:///tmp/proofs.crev"));
+}
+
+#[test]
+fn change_id_passphrase() -> Result<()> {
+    let (_root, local, id) = temp_local_with_id("old")?;
+
+    local.change_current_id_passphrase(&|| Ok("old".into()), &|| Ok("new".into()))?;
+
+    let locked = local.read_current_locked_id()?;
+    assert!(locked.to_unlocked("old").is_err());
+    assert_eq!(locked.to_unlocked("new")?.id.id, id.id.id);
+
+    Ok(())
+}
+
+// The latest own proof is found, and replaced in place by an edited one
+#[test]
+fn edit_last_own_proof() -> Result<()> {
+    let (_root, local, id) = temp_local_with_id("pass")?;
+    assert!(local.find_last_own_proof()?.is_none());
+
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let now = crev_common::now();
+    let mut proofs = vec![];
+    for (other, date) in &[(&a, now), (&b, now + chrono::Duration::seconds(1))] {
+        let mut trust: crev_data::proof::Content = id
+            .id
+            .create_trust_proof(&[other.id.clone()], TrustLevel::High)?
+            .into();
+        trust.set_date(date);
+        let proof = trust.sign_by(&id)?;
+        local.insert(&proof)?;
+        proofs.push(proof);
+    }
+
+    let (path, last, published) = local.find_last_own_proof()?.unwrap();
+    assert_eq!(last.body, proofs[1].body);
+    assert!(!published);
+
+    let mut content = last.content.clone();
+    if let crev_data::proof::Content::Trust(ref mut trust) = content {
+        trust.comment = "edited".into();
+    }
+    let edited = content.sign_by(&id)?;
+    local.replace_proof(&path, &last, &edited)?;
+
+    let mut bodies: Vec<_> = local.proofs_iter()?.map(|proof| proof.body).collect();
+    bodies.sort();
+    let mut expected = vec![proofs[0].body.clone(), edited.body.clone()];
+    expected.sort();
+    assert_eq!(bodies, expected);
+
+    Ok(())
+}
+
+// Fetched repositories are listed with the authors and number of their
+// proofs, skipping directories that weren't fetched
+#[test]
+fn list_fetched_repos() -> Result<()> {
+    let (_root, local) = temp_local()?;
+    assert!(local.list_fetched_repos()?.is_empty());
+
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let c = OwnId::generate_for_git_url("https://c");
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let c_to_b = c.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::Low)?;
+
+    let repo_dir = local.cache_remotes_path().join("example.com-proofs");
+    std::fs::create_dir_all(&repo_dir)?;
+    std::fs::write(
+        repo_dir.join(".crev-http-url"),
+        "https://example.com/proofs.crev",
+    )?;
+    std::fs::write(
+        repo_dir.join("proofs.crev"),
+        format!("{}{}", a_to_b, c_to_b),
+    )?;
+    std::fs::create_dir_all(local.cache_remotes_path().join("unknown"))?;
+
+    let repos = local.list_fetched_repos()?;
+    assert_eq!(repos.len(), 1);
+    assert_eq!(repos[0].url, "https://example.com/proofs.crev");
+    assert_eq!(repos[0].proof_count, 2);
+    assert_eq!(
+        repos[0].author_ids,
+        vec![a.id.id.clone(), c.id.id.clone()].into_iter().collect()
+    );
+    assert!(repos[0].last_commit.is_none());
+
+    Ok(())
+}
+
+// Proofs with a tampered body, or that can't be parsed, are reported
+#[test]
+fn verify_fetched_repo_integrity() -> Result<()> {
+    let (_root, local) = temp_local()?;
+    assert!(local
+        .verify_fetched_repo_integrity("https://example.com/proofs.crev")
+        .is_err());
+
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let tampered = a_to_b.to_string().replace("trust: high", "trust: low");
+    assert_ne!(tampered, a_to_b.to_string());
+
+    let url = "https://example.com/proofs.crev";
+    let repo_dir = local.get_remote_git_cache_path(url)?;
+    std::fs::create_dir_all(&repo_dir)?;
+    std::fs::write(repo_dir.join(".crev-http-url"), url)?;
+    std::fs::write(repo_dir.join("ok.crev"), a_to_b.to_string())?;
+    std::fs::write(repo_dir.join("tampered.crev"), tampered)?;
+
+    let report = local.verify_fetched_repo_integrity(url)?;
+    assert_eq!(report.valid_count, 1);
+    assert_eq!(report.broken.len(), 1);
+    assert_eq!(report.broken[0].0, repo_dir.join("tampered.crev"));
+
+    let all = local.verify_all_fetched_repos_integrity()?;
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].url, url);
+
+    Ok(())
+}
+
+// Only own trust proofs for the given Id are removed, the rest of the
+// file is kept
+#[test]
+fn remove_own_trust_proofs() -> Result<()> {
+    let (_root, local, id) = temp_local_with_id("pass")?;
+
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    for other in &[&a, &b] {
+        let trust = id
+            .id
+            .create_trust_proof(&[other.id.clone()], TrustLevel::High)?;
+        local.insert(&trust.sign_by(&id)?)?;
+    }
+
+    let removed = local.remove_own_trust_proofs_for(&a.id.id)?;
+    assert_eq!(removed.len(), 1);
+    assert!(!removed[0].1);
+
+    let trusted: Vec<_> = local
+        .proofs_iter()?
+        .filter_map(|proof| match proof.content {
+            crev_data::proof::Content::Trust(trust) => Some(trust.ids[0].id.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(trusted, vec![b.id.id.clone()]);
+
+    assert!(local.remove_own_trust_proofs_for(&a.id.id)?.is_empty());
+
+    // a proof that trusts other Ids too can't be removed, and then nothing is
+    let c = OwnId::generate_for_git_url("https://c");
+    let d = OwnId::generate_for_git_url("https://d");
+    let c_only = id
+        .id
+        .create_trust_proof(&[c.id.clone()], TrustLevel::High)?;
+    local.insert(&c_only.sign_by(&id)?)?;
+    let c_and_d = id
+        .id
+        .create_trust_proof(&[c.id.clone(), d.id.clone()], TrustLevel::High)?;
+    std::fs::write(
+        local.get_proofs_dir_path()?.join("c-and-d.crev"),
+        c_and_d.sign_by(&id)?.to_string(),
+    )?;
+    let proof_count = local.proofs_iter()?.count();
+    assert!(local.remove_own_trust_proofs_for(&c.id.id).is_err());
+    assert_eq!(local.proofs_iter()?.count(), proof_count);
+
+    Ok(())
+}
+
+// A signature returned by an external signer must match the Id
+#[test]
+#[cfg(unix)]
+fn external_signer_signature_is_verified() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let content: crev_data::proof::Content = a
+        .as_pubid()
+        .create_trust_proof(vec![b.as_pubid()], TrustLevel::High)?
+        .into();
+
+    let local_signer = id::Signer::Local(a);
+    let proof = local_signer.sign(&content)?;
+    proof.verify()?;
+
+    let signature = proof.signature().to_owned();
+    // the command gets the path of the proof body as an argument
+    let external_signer = id::Signer::External {
+        id: local_signer.as_pubid().clone(),
+        cmd: format!("echo {}; true", signature),
+    };
+    assert_eq!(external_signer.sign(&content)?.signature(), signature);
+
+    let content: crev_data::proof::Content = b
+        .as_pubid()
+        .create_trust_proof(vec![b.as_pubid()], TrustLevel::High)?
+        .into();
+    let external_signer = id::Signer::External {
+        id: b.as_pubid().clone(),
+        cmd: format!("echo {}; true", signature),
+    };
+    assert!(external_signer.sign(&content).is_err());
+
+    Ok(())
+}
+
+// Indexed proofs are the same as the ones read from files, and
+// changed or removed files are picked up
+#[test]
+fn proof_index_follows_proof_files() -> Result<()> {
+    use crate::proof_index::ProofIndex;
+
+    let dir = tempdir::TempDir::new("crev-proof-index")?;
+    let proofs_dir = dir.path().join("proofs");
+    let index_path = dir.path().join("index");
+    std::fs::create_dir_all(&proofs_dir)?;
+
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let c = OwnId::generate_for_git_url("https://c");
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let a_to_c = a.create_signed_trust_proof(vec![c.as_pubid()], TrustLevel::Low)?;
+    let review = a
+        .as_pubid()
+        .create_package_review_proof(
+            crev_data::proof::PackageInfo {
+                id: None,
+                source: "source".into(),
+                name: "name".into(),
+                version: Version::parse("1.0.0").unwrap(),
+                digest: vec![0; 32],
+                digest_type: crev_data::proof::default_digest_type(),
+                revision: "".into(),
+                revision_type: crev_data::proof::default_revision_type(),
+            },
+            default(),
+            "comment".into(),
+        )?
+        .sign_by(&a)?;
+
+    let file_path = proofs_dir.join("trust.crev");
+    std::fs::write(&file_path, a_to_b.to_string())?;
+    std::fs::write(proofs_dir.join("review.crev"), review.to_string())?;
+    let bodies = |proofs: Vec<crev_data::proof::Proof>| -> Vec<String> {
+        proofs.into_iter().map(|proof| proof.body).collect()
+    };
+
+    for _ in 0..2 {
+        let mut index = ProofIndex::open(&index_path);
+        let mut found = bodies(index.proofs_in_dir(proofs_dir.clone()));
+        found.sort();
+        let mut expected = vec![a_to_b.body.clone(), review.body.clone()];
+        expected.sort();
+        assert_eq!(found, expected);
+        index.save()?;
+        assert!(index_path.exists());
+    }
+
+    std::fs::remove_file(proofs_dir.join("review.crev"))?;
+    std::fs::write(&file_path, format!("{}{}", a_to_b, a_to_c))?;
+    let mut index = ProofIndex::open(&index_path);
+    assert_eq!(
+        bodies(index.proofs_in_dir(proofs_dir.clone())),
+        vec![a_to_b.body.clone(), a_to_c.body.clone()]
+    );
+    index.save()?;
+
+    std::fs::remove_file(&file_path)?;
+    let mut index = ProofIndex::open(&index_path);
+    assert!(index.proofs_in_dir(proofs_dir.clone()).is_empty());
+    index.save()?;
+
+    Ok(())
+}
+
+#[test]
+fn dir_digest_of_supported_types() -> Result<()> {
+    let dir = tempdir::TempDir::new("crev-digest")?;
+    std::fs::write(dir.path().join("lib.rs"), "fn main() {}")?;
+    let ignore_list = std::collections::HashSet::<std::path::PathBuf>::new();
+
+    let default_digest = get_dir_digest(dir.path(), &ignore_list)?;
+    assert_eq!(
+        get_dir_digest_of_type(
+            dir.path(),
+            &ignore_list,
+            &crev_data::proof::default_digest_type()
+        )?,
+        Some(default_digest.clone())
+    );
+    for digest_type in SUPPORTED_DIGEST_TYPES {
+        assert!(get_dir_digest_of_type(dir.path(), &ignore_list, digest_type)?.is_some());
+    }
+    assert_ne!(
+        get_dir_digest_of_type(dir.path(), &ignore_list, "sha256")?,
+        Some(default_digest)
+    );
+    assert_eq!(
+        get_dir_digest_of_type(dir.path(), &ignore_list, "md5")?,
+        None
+    );
+
+    Ok(())
+}
+
 #[test]
 fn validate_proof_generated_by_previous_version() -> Result<()> {
     let yaml = r#"
@@ -150,6 +649,99 @@ fn proofdb_distance() -> Result<()> {
     Ok(())
 }
 
+// Every Id that brought another one into the trust set is kept
+#[test]
+fn proofdb_trusted_by() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let c = OwnId::generate_for_git_url("https://c");
+    let d = OwnId::generate_for_git_url("https://d");
+
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let a_to_c = a.create_signed_trust_proof(vec![c.as_pubid()], TrustLevel::Medium)?;
+    let b_to_d = b.create_signed_trust_proof(vec![d.as_pubid()], TrustLevel::High)?;
+    let c_to_d = c.create_signed_trust_proof(vec![d.as_pubid()], TrustLevel::High)?;
+
+    let mut trustdb = ProofDB::new();
+    trustdb.import_from_iter(vec![a_to_b, a_to_c, b_to_d, c_to_d].into_iter());
+    let trust_set = trustdb.calculate_trust_set(a.as_ref(), &default());
+
+    let mut d_sources: Vec<_> = trust_set.trusted_by(d.as_ref()).collect();
+    d_sources.sort();
+    let mut expected = vec![
+        (b.as_ref(), TrustLevel::High),
+        (c.as_ref(), TrustLevel::Medium),
+    ];
+    expected.sort();
+    assert_eq!(d_sources, expected);
+    assert_eq!(
+        trust_set.trusted_by(b.as_ref()).collect::<Vec<_>>(),
+        vec![(a.as_ref(), TrustLevel::High)]
+    );
+    assert_eq!(trust_set.trusted_by(a.as_ref()).count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn proofdb_trust_paths() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let c = OwnId::generate_for_git_url("https://c");
+    let d = OwnId::generate_for_git_url("https://d");
+
+    let distance_params = TrustDistanceParams {
+        high_trust_distance: 1,
+        medium_trust_distance: 10,
+        low_trust_distance: 100,
+        max_distance: 111,
+    };
+
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let b_to_c = b.create_signed_trust_proof(vec![c.as_pubid()], TrustLevel::Medium)?;
+    let a_to_c = a.create_signed_trust_proof(vec![c.as_pubid()], TrustLevel::Low)?;
+    let c_to_a = c.create_signed_trust_proof(vec![a.as_pubid()], TrustLevel::High)?;
+
+    let mut trustdb = ProofDB::new();
+    trustdb.import_from_iter(vec![a_to_b, b_to_c, a_to_c, c_to_a].into_iter());
+    let trust_set = trustdb.calculate_trust_set(a.as_ref(), &distance_params);
+
+    let paths = trustdb.find_trust_paths(&trust_set, a.as_ref(), c.as_ref(), &distance_params, 10);
+    assert_eq!(
+        paths,
+        vec![
+            TrustPath {
+                steps: vec![
+                    (b.id.id.clone(), TrustLevel::High),
+                    (c.id.id.clone(), TrustLevel::Medium)
+                ],
+                distance: 11,
+            },
+            TrustPath {
+                steps: vec![(c.id.id.clone(), TrustLevel::Low)],
+                distance: 100,
+            },
+        ]
+    );
+    assert_eq!(paths[0].effective_trust(), TrustLevel::Medium);
+    assert_eq!(
+        trust_set.get_effective_trust_level(c.as_ref()),
+        paths[0].effective_trust()
+    );
+
+    assert_eq!(
+        trustdb
+            .find_trust_paths(&trust_set, a.as_ref(), c.as_ref(), &distance_params, 1)
+            .len(),
+        1
+    );
+    let d_paths =
+        trustdb.find_trust_paths(&trust_set, a.as_ref(), d.as_ref(), &distance_params, 10);
+    assert!(d_paths.is_empty());
+
+    Ok(())
+}
+
 // A subsequent review of exactly same package version
 // is supposed to overwrite the previous one, and it
 // should be visible in all the user-facing stats, listings
@@ -252,6 +844,7 @@ fn dont_consider_an_empty_review_as_valid() -> Result<()> {
         understanding: Level::None,
         trust_level: Level::None,
         redundancy: 1,
+        since: None,
     };
     assert!(!trustdb
         .verify_package_digest(&Digest::from_vec(digest), &trust_set, &verification_reqs)
@@ -260,6 +853,146 @@ fn dont_consider_an_empty_review_as_valid() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn dont_count_reviews_older_than_since() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let digest = vec![0; 32];
+    let package = crev_data::proof::PackageInfo {
+        id: None,
+        source: "source".into(),
+        name: "name".into(),
+        version: Version::parse("1.0.0").unwrap(),
+        digest: digest.clone(),
+        digest_type: crev_data::proof::default_digest_type(),
+        revision: "".into(),
+        revision_type: crev_data::proof::default_revision_type(),
+    };
+
+    let review = crev_data::proof::review::Review::new_positive();
+
+    let proof1 = a
+        .as_pubid()
+        .create_package_review_proof(package.clone(), review, "a".into())?
+        .sign_by(&a)?;
+
+    let mut trustdb = ProofDB::new();
+    trustdb.import_from_iter(vec![proof1].into_iter());
+    let trust_set = trustdb.calculate_trust_set(&a.id.id, &default());
+    let digest = Digest::from_vec(digest);
+
+    let mut verification_reqs = VerificationRequirements {
+        thoroughness: Level::None,
+        understanding: Level::None,
+        trust_level: Level::None,
+        redundancy: 1,
+        since: None,
+    };
+    assert!(trustdb
+        .verify_package_digest(&digest, &trust_set, &verification_reqs)
+        .is_verified());
+
+    verification_reqs.since = Some(crev_common::now() - chrono::Duration::days(1));
+    assert!(trustdb
+        .verify_package_digest(&digest, &trust_set, &verification_reqs)
+        .is_verified());
+
+    verification_reqs.since = Some(crev_common::now() + chrono::Duration::days(1));
+    assert!(!trustdb
+        .verify_package_digest(&digest, &trust_set, &verification_reqs)
+        .is_verified());
+
+    Ok(())
+}
+
+#[test]
+fn count_only_verifying_reviews_as_independent_reviewers() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let b2 = OwnId::generate_for_git_url("https://b");
+    let c = OwnId::generate_for_git_url("https://c");
+    let d = OwnId::generate_for_git_url("https://d");
+    let e = OwnId::generate_for_git_url("https://e");
+
+    let review_proof = |id: &OwnId, digest: Vec<u8>, review| -> Result<_> {
+        let package = crev_data::proof::PackageInfo {
+            id: None,
+            source: "source".into(),
+            name: "name".into(),
+            version: Version::parse("1.0.0").unwrap(),
+            digest,
+            digest_type: crev_data::proof::default_digest_type(),
+            revision: "".into(),
+            revision_type: crev_data::proof::default_revision_type(),
+        };
+        id.as_pubid()
+            .create_package_review_proof(package, review, "".into())?
+            .sign_by(id)
+    };
+    let shallow = crev_data::proof::review::Review {
+        thoroughness: Level::None,
+        ..crev_data::proof::review::Review::new_positive()
+    };
+
+    let trust = a.create_signed_trust_proof(
+        vec![
+            b.as_pubid(),
+            b2.as_pubid(),
+            c.as_pubid(),
+            d.as_pubid(),
+            e.as_pubid(),
+        ],
+        TrustLevel::High,
+    )?;
+    let positive = crev_data::proof::review::Review::new_positive;
+    let proofs = vec![
+        trust,
+        review_proof(&b, vec![0; 32], positive())?,
+        // same proof repository as `b`
+        review_proof(&b2, vec![0; 32], positive())?,
+        review_proof(
+            &c,
+            vec![0; 32],
+            crev_data::proof::review::Review::new_negative(),
+        )?,
+        review_proof(&d, vec![0; 32], shallow)?,
+        // another digest of the same version
+        review_proof(&e, vec![1; 32], positive())?,
+    ];
+
+    let mut trustdb = ProofDB::new();
+    trustdb.import_from_iter(proofs.into_iter());
+    let trust_set = trustdb.calculate_trust_set(&a.id.id, &default());
+    let digest = Digest::from_vec(vec![0; 32]);
+    let requirements = VerificationRequirements {
+        thoroughness: Level::Low,
+        understanding: Level::None,
+        trust_level: Level::Low,
+        redundancy: 1,
+        since: None,
+    };
+
+    assert_eq!(
+        trustdb.count_independent_reviewers(&digest, &trust_set, &requirements),
+        1
+    );
+    let verdict_of = |id: &OwnId| {
+        trustdb
+            .get_package_review_verdicts(&digest, &trust_set, &requirements)
+            .into_iter()
+            .find(|(review, _)| review.from.id == id.id.id)
+            .map(|(_, verdict)| verdict)
+    };
+    assert_eq!(verdict_of(&c), Some(ReviewVerdict::Negative));
+    assert_eq!(verdict_of(&d), Some(ReviewVerdict::TooShallow));
+    assert_eq!(verdict_of(&e), None);
+    assert_eq!(
+        trustdb.verify_package_digest(&digest, &trust_set, &requirements),
+        VerificationStatus::Negative
+    );
+
+    Ok(())
+}
+
 #[test]
 fn proofdb_distrust() -> Result<()> {
     let a = OwnId::generate_for_git_url("https://a");
@@ -315,3 +1048,42 @@ fn proofdb_distrust() -> Result<()> {
 
     Ok(())
 }
+
+// Cycles in the WoT are reported, but have no effect on trust levels
+#[test]
+fn proofdb_trust_cycles() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+    let c = OwnId::generate_for_git_url("https://c");
+    let d = OwnId::generate_for_git_url("https://d");
+
+    let distance_params = TrustDistanceParams::default();
+
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let b_to_c = b.create_signed_trust_proof(vec![c.as_pubid()], TrustLevel::Medium)?;
+    let c_to_b = c.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+    let c_to_d = c.create_signed_trust_proof(vec![d.as_pubid()], TrustLevel::Low)?;
+
+    let mut trustdb = ProofDB::new();
+    trustdb.import_from_iter(vec![a_to_b, b_to_c, c_to_d].into_iter());
+    let trust_set = trustdb.calculate_trust_set(a.as_ref(), &distance_params);
+    assert!(trustdb.find_trust_cycles(&trust_set).is_empty());
+    let levels_before: Vec<_> = [&a, &b, &c, &d]
+        .iter()
+        .map(|id| trust_set.get_effective_trust_level(id.as_ref()))
+        .collect();
+
+    trustdb.import_from_iter(vec![c_to_b].into_iter());
+    let trust_set = trustdb.calculate_trust_set(a.as_ref(), &distance_params);
+    let levels_after: Vec<_> = [&a, &b, &c, &d]
+        .iter()
+        .map(|id| trust_set.get_effective_trust_level(id.as_ref()))
+        .collect();
+    assert_eq!(levels_before, levels_after);
+
+    let mut expected = vec![b.id.id.clone(), c.id.id.clone()];
+    expected.sort();
+    assert_eq!(trustdb.find_trust_cycles(&trust_set), vec![expected]);
+
+    Ok(())
+}
@@ -333,3 +333,48 @@ fn issues_sanity() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn proofdb_stats() -> Result<()> {
+    let a = OwnId::generate_for_git_url("https://a");
+    let b = OwnId::generate_for_git_url("https://b");
+
+    let a_advisories = build_proof_with_advisories(
+        &a,
+        Version::parse("1.0.0").unwrap(),
+        vec![
+            build_advisory("first", VersionRange::Major),
+            build_advisory("second", VersionRange::Major),
+        ],
+    );
+    let a_issues = build_proof_with_issues(
+        &a,
+        Version::parse("2.0.0").unwrap(),
+        vec![build_issue("someid")],
+    );
+    let b_issues = build_proof_with_issues(
+        &b,
+        Version::parse("1.0.0").unwrap(),
+        vec![build_issue("someid")],
+    );
+    let a_to_b = a.create_signed_trust_proof(vec![b.as_pubid()], TrustLevel::High)?;
+
+    let mut trustdb = ProofDB::new();
+    trustdb.import_from_iter(vec![a_advisories, a_issues, b_issues, a_to_b].into_iter());
+
+    assert_eq!(
+        trustdb.stats(),
+        crate::ProofDBStats {
+            ids: 2,
+            repos: 2,
+            package_reviews: 3,
+            trust_proofs: 1,
+            advisories: 2,
+            issues: 2,
+            reviewed_crates: 1,
+            reviewed_crate_versions: 2,
+        }
+    );
+
+    Ok(())
+}
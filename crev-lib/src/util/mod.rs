@@ -17,8 +17,25 @@ fn get_git_default_editor() -> Result<String> {
     Ok(cfg.get_string("core.editor")?)
 }
 
+/// Environment variable with the editor to use for `crev` only
+pub const EDITOR_ENV: &str = "CREV_EDITOR";
+
+fn get_config_editor() -> Option<String> {
+    crate::Local::new()
+        .and_then(|local| local.load_user_config())
+        .ok()
+        .and_then(|config| config.editor)
+        .filter(|editor| !editor.is_empty())
+}
+
+/// Pick the editor: `$CREV_EDITOR`, `editor` from the user config,
+/// `$VISUAL`, `$EDITOR`, git's `core.editor`, and finally `vi`
 fn get_editor_to_use() -> Result<ffi::OsString> {
-    Ok(if let Some(v) = env::var_os("VISUAL") {
+    Ok(if let Some(v) = env::var_os(EDITOR_ENV) {
+        v
+    } else if let Some(v) = get_config_editor() {
+        v.into()
+    } else if let Some(v) = env::var_os("VISUAL") {
         v
     } else if let Some(v) = env::var_os("EDITOR") {
         v